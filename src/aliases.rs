@@ -0,0 +1,114 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+
+use crate::{version_spec::VersionSpec, Config};
+
+/// The alias used by the shell integration when no other version is requested
+pub const DEFAULT_ALIAS: &str = "default";
+
+/// A user defined name for a version spec, stored as `aliases/<name>`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alias {
+    pub name: String,
+    /// What the alias points to, e.g. `18.19`, `lts/*` or another alias
+    pub target: String,
+}
+
+impl Alias {
+    pub fn get(config: &Config, name: &str) -> Option<Alias> {
+        if !is_alias_name(name) {
+            return None;
+        }
+
+        let target = fs::read_to_string(config.get_aliases_dir().join(name)).ok()?;
+
+        Some(Alias {
+            name: name.to_string(),
+            target: target.trim().to_string(),
+        })
+    }
+
+    pub fn set(config: &Config, name: &str, target: &str) -> Result<Alias> {
+        validate_name(name)?;
+        let target = target.trim();
+        VersionSpec::parse(target)?;
+
+        let aliases_dir = config.get_aliases_dir();
+        fs::create_dir_all(&aliases_dir)?;
+        fs::write(aliases_dir.join(name), format!("{target}\n"))
+            .context(format!("Failed to write alias `{name}`"))?;
+
+        Ok(Alias {
+            name: name.to_string(),
+            target: target.to_string(),
+        })
+    }
+
+    pub fn remove(self, config: &Config) -> Result<()> {
+        fs::remove_file(config.get_aliases_dir().join(&self.name))
+            .context(format!("Failed to remove alias `{}`", self.name))
+    }
+
+    pub fn list(config: &Config) -> Vec<Alias> {
+        let Ok(entries) = config.get_aliases_dir().read_dir() else {
+            return vec![];
+        };
+
+        let mut aliases: Vec<Alias> = entries
+            .flatten()
+            .filter_map(|entry| Alias::get(config, &entry.file_name().to_string_lossy()))
+            .collect();
+
+        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        aliases
+    }
+
+    pub fn spec(&self) -> Result<VersionSpec> {
+        VersionSpec::parse(&self.target).context(format!(
+            "Alias `{}` points to an invalid version `{}`",
+            self.name, self.target
+        ))
+    }
+}
+
+/// Whether `name` looks like an alias rather than a version
+pub fn is_alias_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '-' || char == '_' || char == '.')
+        && name.starts_with(|char: char| char.is_ascii_alphabetic())
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if !is_alias_name(name) {
+        anyhow::bail!(
+            "`{name}` is not a valid alias name, it has to start with a letter and only contain letters, numbers, `-`, `_` and `.`"
+        );
+    }
+
+    if !matches!(VersionSpec::parse(name), Ok(VersionSpec::Alias(_))) {
+        anyhow::bail!("`{name}` can not be used as an alias name since it is already a version");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use crate::aliases::is_alias_name;
+
+    #[test]
+    fn recognizes_alias_names() {
+        assert_that!(is_alias_name("default")).is_true();
+        assert_that!(is_alias_name("work-project_2.1")).is_true();
+        assert_that!(is_alias_name("18")).is_false();
+        assert_that!(is_alias_name("v18")).is_true();
+        assert_that!(is_alias_name("^18")).is_false();
+        assert_that!(is_alias_name("lts/*")).is_false();
+        assert_that!(is_alias_name("")).is_false();
+    }
+}
@@ -1,27 +1,60 @@
-#[cfg(unix)]
-use std::fs::remove_dir_all;
-#[cfg(windows)]
-use std::fs::File;
 #[cfg(windows)]
 use std::io::copy;
 #[cfg(unix)]
+use std::io::Read;
+#[cfg(unix)]
 use std::path::PathBuf;
-use std::{fs::create_dir_all, io::Cursor, path::Path};
+use std::{
+    fs::{create_dir_all, File},
+    path::Path,
+};
 
 use anyhow::Result;
+use clap::ValueEnum;
+
+use crate::output::status;
 #[cfg(unix)]
 use flate2::read::GzDecoder;
 #[cfg(unix)]
 use tar::{Archive, Unpacked};
+#[cfg(unix)]
+use xz2::read::XzDecoder;
 #[cfg(target_os = "windows")]
 use zip::ZipArchive;
 
+/// Which kind of archive to download. Windows always uses `.zip` archives.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ArchiveFormat {
+    /// Use `.tar.xz` if it's available, otherwise `.tar.gz`
+    #[default]
+    Auto,
+    Xz,
+    Gz,
+}
+
+impl ArchiveFormat {
+    /// Extensions of the archives to look for, most preferred first
+    #[cfg(unix)]
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            ArchiveFormat::Auto => &[".tar.xz", ".tar.gz"],
+            ArchiveFormat::Xz => &[".tar.xz"],
+            ArchiveFormat::Gz => &[".tar.gz"],
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn extensions(self) -> &'static [&'static str] {
+        &[".zip"]
+    }
+}
+
 #[cfg(target_os = "windows")]
-pub fn extract_archive(bytes: Vec<u8>, path: &Path) -> Result<()> {
-    let reader = Cursor::new(bytes);
-    let mut archive = ZipArchive::new(reader).unwrap();
+pub fn extract_archive(archive_path: &Path, path: &Path) -> Result<()> {
+    let reader = File::open(archive_path)?;
+    let mut archive = ZipArchive::new(reader)?;
 
-    println!("Extracting...");
+    status!("Extracting...");
 
     for i in 0..archive.len() {
         let mut item = archive.by_index(i).unwrap();
@@ -44,28 +77,22 @@ pub fn extract_archive(bytes: Vec<u8>, path: &Path) -> Result<()> {
         }
     }
 
-    println!(
-        "Extracted to {}",
-        // Have to remove \\?\ prefix 🤮
-        path.to_str()
-            .unwrap()
-            .strip_prefix("\\\\?\\")
-            .unwrap_or_else(|| path.to_str().unwrap())
-    );
-
     Ok(())
 }
 
 #[cfg(unix)]
-pub fn extract_archive(bytes: Vec<u8>, path: &Path) -> Result<()> {
-    let reader = Cursor::new(bytes);
-    let tar = GzDecoder::new(reader);
+pub fn extract_archive(archive_path: &Path, path: &Path) -> Result<()> {
+    let reader = File::open(archive_path)?;
+    let tar: Box<dyn Read> = if archive_path.to_string_lossy().ends_with(".tar.xz") {
+        Box::new(XzDecoder::new(reader))
+    } else {
+        Box::new(GzDecoder::new(reader))
+    };
     let mut archive = Archive::new(tar);
 
-    let version_dir_path = path.to_owned();
-    create_dir_all(&version_dir_path).expect("fuck");
+    create_dir_all(path)?;
 
-    println!("Extracting...");
+    status!("Extracting...");
 
     let result = archive
         .entries()
@@ -93,19 +120,15 @@ pub fn extract_archive(bytes: Vec<u8>, path: &Path) -> Result<()> {
         .collect();
 
     if !errors.is_empty() {
-        remove_dir_all(version_dir_path).expect("Couldn't clean up version.");
-
         return Err(anyhow::anyhow!(
-            "Failed to extract all files:\n{:?}",
+            "Failed to extract all files:\n{}",
             errors
                 .into_iter()
                 .map(|err| err.to_string())
                 .collect::<Vec<String>>()
-                .join("/n")
+                .join("\n")
         ));
     }
 
-    println!("Extracted to {version_dir_path:?}");
-
     Ok(())
 }
@@ -0,0 +1,207 @@
+use std::{
+    fs,
+    fs::File,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+
+use crate::{checksums, Config};
+
+/// An archive in the download cache, stored as `downloads/<sha256>/<file name>`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CachedArchive {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub hash: String,
+    pub size: u64,
+    /// When the archive was last downloaded or used
+    pub modified: SystemTime,
+}
+
+impl CachedArchive {
+    fn from_path(path: PathBuf) -> Option<Self> {
+        let metadata = fs::metadata(&path).ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+
+        Some(CachedArchive {
+            file_name: path.file_name()?.to_string_lossy().to_string(),
+            hash: path.parent()?.file_name()?.to_string_lossy().to_string(),
+            size: metadata.len(),
+            modified: metadata.modified().ok()?,
+            path,
+        })
+    }
+
+    /// Whether the contents of the archive still match its hash
+    pub fn is_intact(&self) -> Result<bool> {
+        let actual = checksums::sha256_file(&self.path)?;
+
+        Ok(actual.eq_ignore_ascii_case(&self.hash))
+    }
+
+    /// Makes sure the contents of the archive still match its hash
+    pub fn verify(&self) -> Result<()> {
+        if !self.is_intact()? {
+            anyhow::bail!("Cached archive {:?} is corrupted", self.path);
+        }
+
+        Ok(())
+    }
+
+    /// Marks the archive as used so it isn't pruned.
+    ///
+    /// Failing to do so only affects pruning, e.g. with a read-only cache, so errors are ignored.
+    pub fn mark_used(&self) {
+        let _ = File::options()
+            .write(true)
+            .open(&self.path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+    }
+
+    pub fn remove(&self) -> Result<()> {
+        match self.path.parent() {
+            Some(hash_dir) => fs::remove_dir_all(hash_dir),
+            None => fs::remove_file(&self.path),
+        }
+        .map_err(anyhow::Error::from)
+    }
+
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.modified)
+            .unwrap_or_default()
+    }
+}
+
+fn downloads_dir(config: &Config) -> PathBuf {
+    config.get_cache_dir().join("downloads")
+}
+
+/// Finds a cached archive by its name and hash
+pub fn find(config: &Config, file_name: &str, hash: &str) -> Option<CachedArchive> {
+    CachedArchive::from_path(
+        downloads_dir(config)
+            .join(hash.to_lowercase())
+            .join(file_name),
+    )
+}
+
+/// Finds the most recently used cached archive with a name, regardless of its hash
+pub fn find_by_name(config: &Config, file_name: &str) -> Option<CachedArchive> {
+    list(config)
+        .into_iter()
+        .filter(|archive| archive.file_name == file_name)
+        .max_by_key(|archive| archive.modified)
+}
+
+/// Where an archive is downloaded to before being verified and moved into the cache
+pub fn partial_path(config: &Config, file_name: &str) -> Result<PathBuf> {
+    let dir = downloads_dir(config);
+    fs::create_dir_all(&dir)?;
+
+    Ok(dir.join(format!("{file_name}.partial")))
+}
+
+/// Moves a downloaded and verified archive into the cache
+pub fn store(config: &Config, file_name: &str, hash: &str, from: &Path) -> Result<CachedArchive> {
+    let hash_dir = downloads_dir(config).join(hash.to_lowercase());
+    fs::create_dir_all(&hash_dir)?;
+
+    let path = hash_dir.join(file_name);
+    fs::rename(from, &path)?;
+
+    CachedArchive::from_path(path)
+        .ok_or_else(|| anyhow::anyhow!("Failed to read back cached {file_name}"))
+}
+
+pub fn list(config: &Config) -> Vec<CachedArchive> {
+    let Ok(hash_dirs) = downloads_dir(config).read_dir() else {
+        return vec![];
+    };
+
+    let mut archives: Vec<CachedArchive> = hash_dirs
+        .flatten()
+        .filter_map(|hash_dir| hash_dir.path().read_dir().ok())
+        .flat_map(|files| files.flatten())
+        .filter_map(|file| CachedArchive::from_path(file.path()))
+        .collect();
+
+    archives.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    archives
+}
+
+/// Formats a byte count, e.g. `41.2 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in UNITS.iter().skip(1) {
+        if size < 1024.0 {
+            break;
+        }
+
+        size /= 1024.0;
+        unit = next_unit;
+    }
+
+    format!("{size:.1} {unit}")
+}
+
+/// Parses durations like `30d`, `12h`, `2w`, `90m` or `45s`
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let unit_index = value
+        .find(|char: char| !char.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(unit_index);
+
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| anyhow::anyhow!("`{value}` is not a valid duration, e.g. `30d` or `12h`"))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        "w" => 60 * 60 * 24 * 7,
+        _ => anyhow::bail!("`{value}` does not have a valid unit, expected one of s, m, h, d, w"),
+    };
+
+    Ok(Duration::from_secs(amount * seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use spectral::prelude::*;
+
+    use crate::cache::downloads::{format_size, parse_duration};
+
+    #[test]
+    fn parses_durations() {
+        assert_that!(parse_duration("45s").unwrap()).is_equal_to(Duration::from_secs(45));
+        assert_that!(parse_duration("12h").unwrap()).is_equal_to(Duration::from_secs(43_200));
+        assert_that!(parse_duration("30d").unwrap()).is_equal_to(Duration::from_secs(2_592_000));
+        assert_that!(parse_duration("2w").unwrap()).is_equal_to(Duration::from_secs(1_209_600));
+        assert_that!(parse_duration("d")).is_err();
+        assert_that!(parse_duration("3y")).is_err();
+        assert_that!(parse_duration("30")).is_err();
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_that!(format_size(512).as_str()).is_equal_to("512 B");
+        assert_that!(format_size(2048).as_str()).is_equal_to("2.0 KiB");
+        assert_that!(format_size(43_200_000).as_str()).is_equal_to("41.2 MiB");
+    }
+}
@@ -0,0 +1,15 @@
+use anyhow::Result;
+
+use crate::{cache::remote::RemoteFile, Config};
+
+/// Returns the contents of the remote `index.json`, using the cached copy when possible.
+///
+/// Falls back to the cached copy with a warning if the index could not be fetched.
+pub fn fetch(config: &Config) -> Result<String> {
+    RemoteFile {
+        url: format!("{}/index.json", config.get_mirror()),
+        name: "index",
+        description: "version index",
+    }
+    .fetch(config)
+}
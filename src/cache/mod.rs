@@ -0,0 +1,14 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub mod downloads;
+pub mod index;
+pub mod remote;
+pub mod schedule;
+
+/// Seconds since the unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,214 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{cache::now, download, Config};
+
+/// A file fetched from a URL, like the version index
+pub struct RemoteFile<'a> {
+    pub url: String,
+    /// Stored as `<name>.json` in the cache dir, with the metadata in `<name>.meta.json`
+    pub name: &'a str,
+    /// Used in messages, e.g. `version index`
+    pub description: &'a str,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, Eq, PartialEq)]
+struct CacheMetadata {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// When the cached index was last fetched or revalidated, in seconds since the unix epoch
+    fetched_at: u64,
+}
+
+struct CachedFile {
+    metadata: CacheMetadata,
+    body: String,
+}
+
+impl CachedFile {
+    fn read(config: &Config, file: &RemoteFile) -> Option<Self> {
+        let metadata = fs::read_to_string(file.metadata_path(config)).ok()?;
+        let metadata: CacheMetadata = serde_json::from_str(&metadata).ok()?;
+        let body = fs::read_to_string(file.path(config)).ok()?;
+
+        Some(CachedFile { metadata, body })
+    }
+
+    fn write(&self, config: &Config, file: &RemoteFile) -> Result<()> {
+        fs::create_dir_all(config.get_cache_dir())?;
+        fs::write(file.path(config), &self.body)?;
+        fs::write(
+            file.metadata_path(config),
+            serde_json::to_string(&self.metadata)?,
+        )?;
+
+        Ok(())
+    }
+
+    fn age(&self) -> u64 {
+        now().saturating_sub(self.metadata.fetched_at)
+    }
+
+    fn is_fresh(&self, config: &Config, url: &str) -> bool {
+        self.metadata.url == url && self.age() < config.index_ttl
+    }
+}
+
+impl RemoteFile<'_> {
+    fn path(&self, config: &Config) -> PathBuf {
+        config.get_cache_dir().join(format!("{}.json", self.name))
+    }
+
+    fn metadata_path(&self, config: &Config) -> PathBuf {
+        config
+            .get_cache_dir()
+            .join(format!("{}.meta.json", self.name))
+    }
+
+    /// Contains the URL of the last failed fetch, and is modified when it failed
+    fn failure_path(&self, config: &Config) -> PathBuf {
+        config.get_cache_dir().join(format!("{}.failed", self.name))
+    }
+
+    /// Returns the contents of the file, using the cached copy when possible.
+    ///
+    /// Falls back to the cached copy with a warning if the file could not be fetched.
+    pub fn fetch(&self, config: &Config) -> Result<String> {
+        let cached = CachedFile::read(config, self);
+
+        if config.offline {
+            return cached.map(|cached| cached.body).context(format!(
+                "There is no cached {} to use in offline mode, run the command without --offline first.",
+                self.description
+            ));
+        }
+
+        if let Some(cached) = cached
+            .as_ref()
+            .filter(|cached| cached.is_fresh(config, &self.url))
+        {
+            return Ok(cached.body.clone());
+        }
+
+        match self.refresh(config, cached.as_ref()) {
+            Ok(body) => Ok(body),
+            Err(err) => match cached {
+                Some(cached) => {
+                    eprintln!(
+                        "⚠️ Failed to fetch the {}, using the cached one from {} minutes ago.\n{err}",
+                        self.description,
+                        cached.age() / 60
+                    );
+
+                    Ok(cached.body)
+                },
+                None => Err(err),
+            },
+        }
+    }
+
+    /// Like [`RemoteFile::fetch`], for files commands can do without.
+    ///
+    /// A failure is only reported once, and the file isn't fetched again until `--index-ttl` has
+    /// passed, so commands don't wait for an unreachable server every time.
+    pub fn fetch_optional(&self, config: &Config) -> Option<String> {
+        let cached = CachedFile::read(config, self);
+
+        if config.offline
+            || self.failed_recently(config)
+            || cached
+                .as_ref()
+                .is_some_and(|cached| cached.is_fresh(config, &self.url))
+        {
+            return cached.map(|cached| cached.body);
+        }
+
+        match self.refresh(config, cached.as_ref()) {
+            Ok(body) => Some(body),
+            Err(err) => {
+                eprintln!(
+                    "⚠️ Failed to fetch the {}, trying again in {} minutes.\n{err}",
+                    self.description,
+                    config.index_ttl / 60
+                );
+                if let Err(err) = fs::create_dir_all(config.get_cache_dir())
+                    .and_then(|_| fs::write(self.failure_path(config), &self.url))
+                {
+                    eprintln!("⚠️ Failed to cache the {} failure: {err}", self.description);
+                }
+
+                cached.map(|cached| cached.body)
+            },
+        }
+    }
+
+    fn failed_recently(&self, config: &Config) -> bool {
+        let path = self.failure_path(config);
+        let is_same_url = fs::read_to_string(&path).is_ok_and(|url| url == self.url);
+        let failed_at = fs::metadata(&path).and_then(|metadata| metadata.modified());
+
+        is_same_url
+            && failed_at.is_ok_and(|failed_at| {
+                failed_at
+                    .elapsed()
+                    .is_ok_and(|elapsed| elapsed.as_secs() < config.index_ttl)
+            })
+    }
+
+    /// Fetches the file and caches it
+    fn refresh(&self, config: &Config, cached: Option<&CachedFile>) -> Result<String> {
+        let file = revalidate(self, cached)?;
+        let _ = fs::remove_file(self.failure_path(config));
+
+        if let Err(err) = file.write(config, self) {
+            eprintln!("⚠️ Failed to cache the {}: {err}", self.description);
+        }
+
+        Ok(file.body)
+    }
+}
+
+fn revalidate(file: &RemoteFile, cached: Option<&CachedFile>) -> Result<CachedFile> {
+    let url = file.url.as_str();
+    let cached = cached.filter(|cached| cached.metadata.url == url);
+    let mut request = download::agent().get(url);
+
+    if let Some(cached) = cached {
+        if let Some(etag) = &cached.metadata.etag {
+            request = request.set("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &cached.metadata.last_modified {
+            request = request.set("If-Modified-Since", last_modified);
+        }
+    }
+
+    let response = request.call()?;
+
+    if response.status() == 304 {
+        if let Some(cached) = cached {
+            return Ok(CachedFile {
+                metadata: CacheMetadata {
+                    fetched_at: now(),
+                    ..cached.metadata.clone()
+                },
+                body: cached.body.clone(),
+            });
+        }
+    }
+
+    let metadata = CacheMetadata {
+        url: url.to_string(),
+        etag: response.header("ETag").map(str::to_string),
+        last_modified: response.header("Last-Modified").map(str::to_string),
+        fetched_at: now(),
+    };
+    let body = response.into_string().context(format!(
+        "Failed to read the {} from {url}",
+        file.description
+    ))?;
+
+    Ok(CachedFile { metadata, body })
+}
@@ -0,0 +1,13 @@
+use crate::{cache::remote::RemoteFile, Config};
+
+/// Returns the contents of the Node.js release schedule, using the cached copy when possible.
+///
+/// `None` if no schedule is configured, or it could not be fetched.
+pub fn fetch(config: &Config) -> Option<String> {
+    RemoteFile {
+        url: config.get_release_schedule_url()?.to_string(),
+        name: "schedule",
+        description: "release schedule",
+    }
+    .fetch_optional(config)
+}
@@ -0,0 +1,147 @@
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+
+/// Finds the expected SHA-256 hash of `file_name` in the contents of a `SHASUMS256.txt` file.
+///
+/// Each line of the file is in the format `<hash>  <file name>`.
+pub fn find_hash<'a>(shasums: &'a str, file_name: &str) -> Option<&'a str> {
+    shasums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?;
+
+        (name == file_name).then_some(hash)
+    })
+}
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    Ok(to_hex(&file_hasher(path)?.finalize()))
+}
+
+/// Returns a hasher that has already hashed the contents of `path`
+pub fn file_hasher(path: &Path) -> Result<Sha256> {
+    let mut reader = HashingReader::new(File::open(path)?);
+    io::copy(&mut reader, &mut io::sink())?;
+
+    Ok(reader.hasher)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Hashes everything that is read through it
+pub struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::continue_from(inner, Sha256::new())
+    }
+
+    /// Continues hashing where `hasher` left off, e.g. when resuming a download
+    pub fn continue_from(inner: R, hasher: Sha256) -> Self {
+        HashingReader { inner, hasher }
+    }
+
+    /// Returns the hex encoded SHA-256 hash of everything read so far
+    pub fn finish(self) -> String {
+        to_hex(&self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+
+        Ok(read)
+    }
+}
+
+/// Makes sure the `actual` hash matches the one listed for `file_name` in `shasums`
+pub fn verify(actual: &str, file_name: &str, shasums: &str) -> Result<()> {
+    let expected = find_hash(shasums, file_name).context(format!(
+        "Could not find a checksum for {file_name} in SHASUMS256.txt"
+    ))?;
+
+    if !expected.eq_ignore_ascii_case(actual) {
+        anyhow::bail!(
+            "Checksum mismatch for {file_name}!\nexpected: {expected}\nactual:   {actual}\nRefusing to extract the downloaded archive."
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use std::io::{self, Read};
+
+    use sha2::{Digest, Sha256};
+
+    use crate::checksums::{find_hash, to_hex, verify, HashingReader};
+
+    fn sha256_hex(bytes: &[u8]) -> String {
+        to_hex(&Sha256::digest(bytes))
+    }
+
+    const SHASUMS: &str = "\
+a5d3b8c9d4e6ba7e5b5b5e2d0b8d5c0e0f3a8d2c4f3b2a1e0d9c8b7a6f5e4d3c  node-v20.11.0-darwin-arm64.tar.gz
+2cf0f3a8d2c4f3b2a1e0d9c8b7a6f5e4d3ca5d3b8c9d4e6ba7e5b5b5e2d0b8d5  node-v20.11.0-linux-x64.tar.gz
+";
+
+    #[test]
+    fn finds_hash_for_file() {
+        assert_that!(find_hash(SHASUMS, "node-v20.11.0-linux-x64.tar.gz")).is_equal_to(Some(
+            "2cf0f3a8d2c4f3b2a1e0d9c8b7a6f5e4d3ca5d3b8c9d4e6ba7e5b5b5e2d0b8d5",
+        ));
+        assert_that!(find_hash(SHASUMS, "node-v20.11.0-linux-x64.tar")).is_none();
+    }
+
+    #[test]
+    fn verifies_matching_hash() {
+        let bytes = b"not actually node";
+        let shasums = format!("{}  node.tar.gz\n", sha256_hex(bytes));
+
+        assert_that!(verify(&sha256_hex(bytes), "node.tar.gz", &shasums)).is_ok();
+    }
+
+    #[test]
+    fn rejects_mismatched_hash() {
+        let result = verify(
+            &sha256_hex(b"tampered"),
+            "node-v20.11.0-linux-x64.tar.gz",
+            SHASUMS,
+        );
+
+        let message = result.unwrap_err().to_string();
+        assert_that!(message)
+            .contains("expected: 2cf0f3a8d2c4f3b2a1e0d9c8b7a6f5e4d3ca5d3b8c9d4e6ba7e5b5b5e2d0b8d5");
+        assert_that!(message).contains(sha256_hex(b"tampered").as_str());
+    }
+
+    #[test]
+    fn hashes_while_reading() {
+        let mut reader = HashingReader::new(&b"streamed bytes"[..]);
+        let mut output = vec![];
+        reader.read_to_end(&mut output).unwrap();
+
+        assert_that!(output.as_slice()).is_equal_to(&b"streamed bytes"[..]);
+        assert_that!(reader.finish()).is_equal_to(sha256_hex(b"streamed bytes"));
+
+        let mut empty = HashingReader::new(io::empty());
+        io::copy(&mut empty, &mut io::sink()).unwrap();
+        assert_that!(empty.finish()).is_equal_to(sha256_hex(b""));
+    }
+}
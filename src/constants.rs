@@ -10,13 +10,6 @@ pub const PLATFORM: &str = "darwin";
 #[cfg(target_os = "linux")]
 pub const PLATFORM: &str = "linux";
 
-#[cfg(target_os = "windows")]
-pub const EXT: &str = ".zip";
-#[cfg(target_os = "macos")]
-pub const EXT: &str = ".tar.gz";
-#[cfg(target_os = "linux")]
-pub const EXT: &str = ".tar.gz";
-
 #[cfg(target_arch = "x86_64")]
 pub const ARCH: &str = "x64";
 #[cfg(target_arch = "x86")]
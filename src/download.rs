@@ -0,0 +1,391 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Read, Write},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+use sha2::{Digest, Sha256};
+use ureq::Agent;
+
+use crate::{
+    checksums::{self, HashingReader},
+    Config,
+};
+
+pub fn agent() -> Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(30))
+        .timeout_read(Duration::from_secs(120))
+        .timeout_write(Duration::from_secs(120))
+        .build()
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every following one
+    pub backoff: Duration,
+}
+
+impl From<&Config> for RetryPolicy {
+    fn from(config: &Config) -> Self {
+        RetryPolicy {
+            max_attempts: config.max_download_attempts.max(1),
+            backoff: Duration::from_millis(config.retry_backoff),
+        }
+    }
+}
+
+enum AttemptError {
+    /// Network errors worth retrying, e.g. connection resets, 5xx responses or truncated bodies
+    Transient(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+/// Streams the response from `url` into `path`, showing a progress bar while doing so.
+///
+/// Transient errors are retried according to `retry`, resuming from what was already downloaded
+/// into `path` - also by previous runs - with HTTP Range requests.
+///
+/// Returns the SHA-256 hash of the downloaded file.
+pub fn download_to_file(
+    agent: &Agent,
+    url: &str,
+    path: &Path,
+    retry: &RetryPolicy,
+) -> Result<String> {
+    with_retries(retry, || download_attempt(agent, url, path))
+}
+
+/// Downloads a small file like `SHASUMS256.txt` into memory, retrying transient errors
+pub fn download_to_bytes(agent: &Agent, url: &str, retry: &RetryPolicy) -> Result<Vec<u8>> {
+    with_retries(retry, || {
+        let response = agent.get(url).call().map_err(classify)?;
+
+        let mut bytes = vec![];
+        response
+            .into_reader()
+            .read_to_end(&mut bytes)
+            .context("Connection lost while downloading")
+            .map_err(AttemptError::Transient)?;
+
+        Ok(bytes)
+    })
+}
+
+/// Whether a download failed because the file does not exist on the server
+pub fn is_not_found(err: &anyhow::Error) -> bool {
+    matches!(err.downcast_ref(), Some(ureq::Error::Status(404, _)))
+}
+
+fn with_retries<T>(
+    retry: &RetryPolicy,
+    mut attempt_fn: impl FnMut() -> Result<T, AttemptError>,
+) -> Result<T> {
+    let mut attempt = 1;
+
+    loop {
+        match attempt_fn() {
+            Ok(result) => return Ok(result),
+            Err(AttemptError::Transient(err)) if attempt < retry.max_attempts => {
+                let delay = retry.backoff * 2u32.saturating_pow(attempt - 1);
+                eprintln!(
+                    "⚠️ Download failed: {err}\nRetrying in {:.1}s ({attempt}/{})...",
+                    delay.as_secs_f32(),
+                    retry.max_attempts - 1
+                );
+
+                thread::sleep(delay);
+                attempt += 1;
+            },
+            Err(AttemptError::Transient(err) | AttemptError::Fatal(err)) => return Err(err),
+        }
+    }
+}
+
+fn download_attempt(agent: &Agent, url: &str, path: &Path) -> Result<String, AttemptError> {
+    let fatal = |err: std::io::Error| AttemptError::Fatal(err.into());
+
+    let downloaded = fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    let mut request = agent.get(url);
+    if downloaded > 0 {
+        request = request.set("Range", &format!("bytes={downloaded}-"));
+    }
+
+    let response = match request.call() {
+        Ok(response) => response,
+        // The partial file is either complete or broken, so start over
+        Err(ureq::Error::Status(416, _)) => {
+            fs::remove_file(path).map_err(fatal)?;
+
+            return Err(AttemptError::Transient(anyhow::anyhow!(
+                "Could not resume the download, restarting it"
+            )));
+        },
+        Err(err) => return Err(classify(err)),
+    };
+
+    // Servers not supporting Range requests send the whole file again
+    let is_resuming = response.status() == 206;
+    if is_resuming && content_range_start(&response) != Some(downloaded) {
+        fs::remove_file(path).map_err(fatal)?;
+
+        return Err(AttemptError::Transient(anyhow::anyhow!(
+            "The server resumed the download at the wrong offset, restarting it"
+        )));
+    }
+    let (hasher, offset) = if is_resuming {
+        let hasher = checksums::file_hasher(path).map_err(AttemptError::Fatal)?;
+        (hasher, downloaded)
+    } else {
+        (Sha256::new(), 0)
+    };
+
+    // Some mirrors send chunked responses without a Content-Length
+    let length: Option<u64> = response
+        .header("Content-Length")
+        .and_then(|length| length.parse().ok())
+        .map(|length: u64| length + offset);
+    let progress = progress_bar(length);
+    progress.set_position(offset);
+
+    let file = File::options()
+        .create(true)
+        .write(true)
+        .append(is_resuming)
+        .truncate(!is_resuming)
+        .open(path)
+        .map_err(fatal)?;
+    let mut writer = BufWriter::new(file);
+    let mut reader =
+        HashingReader::continue_from(progress.wrap_read(response.into_reader()), hasher);
+
+    // Not using io::copy since read and write errors need to be handled differently
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .context("Connection lost while downloading")
+            .map_err(AttemptError::Transient)?;
+        if read == 0 {
+            break;
+        }
+
+        writer.write_all(&buffer[..read]).map_err(fatal)?;
+    }
+
+    writer.flush().map_err(fatal)?;
+    progress.finish_and_clear();
+
+    Ok(reader.finish())
+}
+
+/// The first byte of a partial response, from e.g. `Content-Range: bytes 5-9/10`
+fn content_range_start(response: &ureq::Response) -> Option<u64> {
+    response
+        .header("Content-Range")?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn classify(err: ureq::Error) -> AttemptError {
+    match err {
+        ureq::Error::Status(status, _) if status < 500 && status != 429 => {
+            AttemptError::Fatal(err.into())
+        },
+        _ => AttemptError::Transient(err.into()),
+    }
+}
+
+fn progress_bar(length: Option<u64>) -> ProgressBar {
+    match length {
+        Some(length) => ProgressBar::new(length).with_style(
+            ProgressStyle::with_template(
+                "{bar:40} {bytes}/{total_bytes} ({bytes_per_sec}, {eta} left)",
+            )
+            .unwrap(),
+        ),
+        None => ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template("{spinner} {bytes} ({bytes_per_sec})").unwrap(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use sha2::{Digest, Sha256};
+    use spectral::prelude::*;
+
+    use crate::{
+        checksums::to_hex,
+        download::{agent, download_to_bytes, download_to_file, RetryPolicy},
+    };
+
+    const RETRIES: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        backoff: Duration::from_millis(1),
+    };
+
+    /// Serves one raw response per connection, recording the requests it receives
+    fn serve(responses: Vec<&'static [u8]>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/node.tar.gz", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let received = requests.clone();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+
+                let mut request = String::new();
+                let mut reader = BufReader::new(&stream);
+                while reader.read_line(&mut request).unwrap_or(0) > 2 {}
+                received.lock().unwrap().push(request);
+
+                stream.write_all(response).unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nvm-download-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn sha256_hex(bytes: &[u8]) -> String {
+        to_hex(&Sha256::digest(bytes))
+    }
+
+    #[test]
+    fn downloads_chunked_response_without_content_length() {
+        let (url, _) = serve(vec![b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n6\r\nchunky\r\n5\r\n bits\r\n0\r\n\r\n"]);
+        let path = temp_file("chunked.tar.gz");
+
+        let hash = download_to_file(&agent(), &url, &path, &RETRIES).unwrap();
+
+        assert_that!(fs::read_to_string(&path).unwrap()).is_equal_to("chunky bits".to_string());
+        assert_that!(hash).is_equal_to(sha256_hex(b"chunky bits"));
+    }
+
+    #[test]
+    fn resumes_truncated_download() {
+        let (url, requests) = serve(vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nhello",
+            b"HTTP/1.1 206 Partial Content\r\nContent-Length: 5\r\nContent-Range: bytes 5-9/10\r\nConnection: close\r\n\r\nworld",
+        ]);
+        let path = temp_file("truncated.tar.gz");
+
+        let hash = download_to_file(&agent(), &url, &path, &RETRIES).unwrap();
+
+        assert_that!(fs::read_to_string(&path).unwrap()).is_equal_to("helloworld".to_string());
+        assert_that!(hash).is_equal_to(sha256_hex(b"helloworld"));
+        assert_that!(requests.lock().unwrap()[1]).contains("bytes=5-");
+    }
+
+    #[test]
+    fn restarts_when_server_resumes_at_wrong_offset() {
+        let (url, requests) = serve(vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nhello",
+            b"HTTP/1.1 206 Partial Content\r\nContent-Length: 7\r\nContent-Range: bytes 3-9/10\r\nConnection: close\r\n\r\nloworld",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nhelloworld",
+        ]);
+        let path = temp_file("wrong-offset.tar.gz");
+
+        let hash = download_to_file(&agent(), &url, &path, &RETRIES).unwrap();
+
+        assert_that!(fs::read_to_string(&path).unwrap()).is_equal_to("helloworld".to_string());
+        assert_that!(hash).is_equal_to(sha256_hex(b"helloworld"));
+        assert_that!(requests.lock().unwrap()[2].contains("Range")).is_false();
+    }
+
+    #[test]
+    fn restarts_when_server_ignores_range() {
+        let (url, _) = serve(vec![
+            b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nhello",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nhelloworld",
+        ]);
+        let path = temp_file("no-range.tar.gz");
+
+        let hash = download_to_file(&agent(), &url, &path, &RETRIES).unwrap();
+
+        assert_that!(fs::read_to_string(&path).unwrap()).is_equal_to("helloworld".to_string());
+        assert_that!(hash).is_equal_to(sha256_hex(b"helloworld"));
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let (url, _) = serve(vec![
+            b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nnode",
+        ]);
+        let path = temp_file("unavailable.tar.gz");
+
+        let hash = download_to_file(&agent(), &url, &path, &RETRIES).unwrap();
+
+        assert_that!(hash).is_equal_to(sha256_hex(b"node"));
+    }
+
+    #[test]
+    fn retries_small_downloads() {
+        let (url, _) = serve(vec![
+            b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 6\r\nConnection: close\r\n\r\nshasum",
+        ]);
+
+        let bytes = download_to_bytes(&agent(), &url, &RETRIES).unwrap();
+
+        assert_that!(bytes).is_equal_to(b"shasum".to_vec());
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let (url, requests) = serve(vec![
+            b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let path = temp_file("broken.tar.gz");
+
+        let result = download_to_file(&agent(), &url, &path, &RETRIES);
+
+        assert_that!(result).is_err();
+        assert_that!(requests.lock().unwrap().len()).is_equal_to(3);
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let (url, requests) = serve(vec![
+            b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let path = temp_file("missing.tar.gz");
+
+        let result = download_to_file(&agent(), &url, &path, &RETRIES);
+
+        assert_that!(result).is_err();
+        assert_that!(requests.lock().unwrap().len()).is_equal_to(1);
+    }
+}
@@ -1,94 +1,203 @@
-use std::{fs, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
-use itertools::Itertools;
-use node_semver::Range;
+use anyhow::Result;
+use clap::ValueEnum;
+
+use crate::{files::package_json::OnFail, version_spec::VersionSpec, Config};
 
 pub mod package_json;
+pub mod tool_versions;
 
 const PACKAGE_JSON_FILE_NAME: &str = "package.json";
 const NVMRC_FILE_NAME: &str = ".nvmrc";
 const NODE_VERSION_FILE_NAME: &str = ".node-version";
 const ASDF_FILE_NAME: &str = ".tool-versions";
 
-pub enum VersionFile {
-    Nvmrc(Range),
-    PackageJson(Range),
-    Asdf(Range),
+/// Error for commands that need a version when none was passed and no version file was found
+pub const NO_VERSION_ERROR: &str = "You did not pass a version and we did not find any version files (package.json#volta.node, package.json#devEngines.runtime, package.json#engines.node, .nvmrc, .node-version, .tool-versions) in the current directory or its parents.";
+/// Same as [`NO_VERSION_ERROR`], for commands falling back to the `default` alias
+pub const NO_VERSION_OR_DEFAULT_ERROR: &str = "You did not pass a version, we did not find any version files (package.json#volta.node, package.json#devEngines.runtime, package.json#engines.node, .nvmrc, .node-version, .tool-versions) in the current directory or its parents and there is no `default` alias.";
+
+/// Where the search for version files in parent directories stops
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SearchBoundary {
+    /// Stop at the root of the git repository, or the home directory outside of repositories
+    #[default]
+    Git,
+    /// Stop at the home directory
+    Home,
+    /// Search all the way up to the root of the filesystem
+    Root,
+}
+
+/// A kind of file that can specify which node version to use
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VersionSource {
+    /// `volta.node`, `devEngines.runtime` or `engines.node` in `package.json`, in that order
+    PackageJson,
+    Nvmrc,
+    NodeVersion,
+    /// The `nodejs` entry of asdf's `.tool-versions`
+    ToolVersions,
 }
 
-impl VersionFile {
-    pub fn range(self) -> Range {
+impl VersionSource {
+    /// The default precedence, used within each directory that is searched
+    pub const DEFAULT_ORDER: [VersionSource; 4] = [
+        VersionSource::PackageJson,
+        VersionSource::Nvmrc,
+        VersionSource::NodeVersion,
+        VersionSource::ToolVersions,
+    ];
+
+    pub fn file_name(self) -> &'static str {
         match self {
-            VersionFile::Nvmrc(range) => range,
-            VersionFile::PackageJson(range) => range,
-            VersionFile::Asdf(range) => range,
+            VersionSource::PackageJson => PACKAGE_JSON_FILE_NAME,
+            VersionSource::Nvmrc => NVMRC_FILE_NAME,
+            VersionSource::NodeVersion => NODE_VERSION_FILE_NAME,
+            VersionSource::ToolVersions => ASDF_FILE_NAME,
         }
     }
-}
 
-pub fn get_version_file() -> Option<VersionFile> {
-    if PathBuf::from(PACKAGE_JSON_FILE_NAME).exists() {
-        let parse_result =
-            package_json::PackageJson::try_from(PathBuf::from(PACKAGE_JSON_FILE_NAME));
-
-        if let Ok(parse_result) = parse_result {
-            return parse_result
-                .engines
-                .and_then(|engines| engines.node)
-                .map(VersionFile::PackageJson);
-        } else {
-            println!(
-                "Failed to parse package.json: {}",
-                parse_result.unwrap_err()
-            );
+    /// Why a file of this kind can exist without specifying a version
+    pub fn missing_reason(self) -> &'static str {
+        match self {
+            VersionSource::PackageJson => {
+                "it has no `volta.node`, `devEngines.runtime` for node or `engines.node`"
+            },
+            VersionSource::ToolVersions => "it has no `nodejs` entry",
+            VersionSource::Nvmrc | VersionSource::NodeVersion => "it is empty",
         }
     }
 
-    if let Some(existing_file) = [NVMRC_FILE_NAME, NODE_VERSION_FILE_NAME]
-        .iter()
-        .find_or_first(|&path| PathBuf::from(path).exists())
-    {
-        let contents = fs::read_to_string(existing_file);
-
-        if let Ok(contents) = contents {
-            let parse_result = Range::parse(contents.trim());
-
-            if let Ok(parse_result) = parse_result {
-                return Some(VersionFile::Nvmrc(parse_result));
-            } else {
-                println!(
-                    "Failed to parse {}: '{}'",
-                    existing_file,
-                    parse_result.unwrap_err().input(),
-                );
-            }
+    /// Reads the version spec from `path`, which is `None` if the file doesn't specify one
+    fn parse(self, config: &Config, path: &Path) -> Result<Option<(VersionSpec, OnFail)>> {
+        let spec = match self {
+            VersionSource::PackageJson => {
+                return package_json::PackageJson::try_from(path.to_path_buf())?.node_version();
+            },
+            VersionSource::Nvmrc | VersionSource::NodeVersion => {
+                let contents = fs::read_to_string(path)?;
+                if contents.trim().is_empty() {
+                    return Ok(None);
+                }
+
+                Some(VersionSpec::parse(&contents)?)
+            },
+            VersionSource::ToolVersions => {
+                let specs =
+                    tool_versions::ToolVersions::try_from(path.to_path_buf())?.node_versions()?;
+
+                specs.map(|specs| first_installed(config, specs))
+            },
+        };
+
+        Ok(spec.map(|spec| (spec, OnFail::Error)))
+    }
+}
+
+pub struct VersionFile {
+    pub path: PathBuf,
+    pub spec: VersionSpec,
+    /// What to do when no installed version matches, only configurable through `devEngines`
+    pub on_fail: OnFail,
+}
+
+/// An existing file that was considered while looking for a version file
+pub struct Candidate {
+    pub path: PathBuf,
+    pub source: VersionSource,
+    /// `None` if the file exists but doesn't specify a version
+    pub spec: Result<Option<VersionSpec>>,
+    pub on_fail: OnFail,
+}
+
+/// Finds the version file with the highest precedence, see [`candidates`]
+pub fn get_version_file(config: &Config) -> Option<VersionFile> {
+    for candidate in candidates(config) {
+        match candidate.spec {
+            Ok(Some(spec)) => {
+                return Some(VersionFile {
+                    path: candidate.path,
+                    spec,
+                    on_fail: candidate.on_fail,
+                })
+            },
+            Ok(None) => {},
+            Err(err) => eprintln!("Failed to parse {:?}: '{err}'", candidate.path),
         }
     }
 
-    if PathBuf::from(ASDF_FILE_NAME).exists() {
-        let contents = fs::read_to_string(ASDF_FILE_NAME);
-
-        if let Ok(contents) = contents {
-            let version_string = contents
-                .lines()
-                .find(|line| line.starts_with("nodejs"))
-                .and_then(|line| line.split(' ').nth(1));
-
-            if let Some(version_string) = version_string {
-                let parse_result = Range::parse(version_string);
-
-                if let Ok(parse_result) = parse_result {
-                    return Some(VersionFile::Asdf(parse_result));
-                } else {
-                    println!(
-                        "Failed to parse {}: '{}'",
-                        ASDF_FILE_NAME,
-                        parse_result.unwrap_err().input(),
-                    );
+    None
+}
+
+/// Lists the existing version files from highest to lowest precedence.
+///
+/// The current directory is checked first and then its parents up to the configured boundary,
+/// looking for the configured sources in order within each directory.
+pub fn candidates(config: &Config) -> impl Iterator<Item = Candidate> + '_ {
+    search_dirs(config).into_iter().flat_map(move |dir| {
+        config
+            .version_file_order
+            .iter()
+            .map(|source| (dir.join(source.file_name()), *source))
+            .filter(|(path, _)| path.is_file())
+            .map(|(path, source)| {
+                let (spec, on_fail) = match source.parse(config, &path) {
+                    Ok(Some((spec, on_fail))) => (Ok(Some(spec)), on_fail),
+                    Ok(None) => (Ok(None), OnFail::default()),
+                    Err(err) => (Err(err), OnFail::default()),
+                };
+
+                Candidate {
+                    path,
+                    source,
+                    spec,
+                    on_fail,
                 }
-            }
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Picks the first of several fallback versions that is installed, or the first one if none are
+fn first_installed(config: &Config, mut specs: Vec<VersionSpec>) -> VersionSpec {
+    if specs.len() > 1 {
+        if let Some(index) = specs.iter().position(|spec| {
+            spec.find_installed(config)
+                .is_ok_and(|installed| !installed.is_empty())
+        }) {
+            return specs.swap_remove(index);
         }
     }
 
-    None
+    specs.swap_remove(0)
+}
+
+/// The current directory followed by the parents that are searched for version files
+pub fn search_dirs(config: &Config) -> Vec<PathBuf> {
+    let Ok(current_dir) = env::current_dir() else {
+        return vec![];
+    };
+    let home_dir = dirs::home_dir();
+
+    let mut search_dirs = vec![];
+    for dir in current_dir.ancestors() {
+        search_dirs.push(dir.to_path_buf());
+
+        let is_home_dir = home_dir.as_deref() == Some(dir);
+        let is_boundary = match config.version_file_boundary {
+            SearchBoundary::Git => is_home_dir || dir.join(".git").exists(),
+            SearchBoundary::Home => is_home_dir,
+            SearchBoundary::Root => false,
+        };
+        if is_boundary {
+            break;
+        }
+    }
+
+    search_dirs
 }
@@ -1,8 +1,11 @@
 use std::{fs, path::PathBuf};
 
+use anyhow::Result;
 use node_semver::Range;
 use serde::Deserialize;
 
+use crate::version_spec::VersionSpec;
+
 #[derive(Clone, Deserialize, Debug, Eq, PartialEq)]
 pub struct PackageJson {
     #[serde()]
@@ -11,6 +14,10 @@ pub struct PackageJson {
     pub version: Option<String>,
     #[serde()]
     pub engines: Option<PackageJsonEngines>,
+    #[serde()]
+    pub volta: Option<PackageJsonVolta>,
+    #[serde(rename = "devEngines")]
+    pub dev_engines: Option<PackageJsonDevEngines>,
 }
 
 #[derive(Clone, Deserialize, Debug, Eq, PartialEq)]
@@ -19,6 +26,71 @@ pub struct PackageJsonEngines {
     pub node: Option<Range>,
 }
 
+/// Volta pins an exact version, e.g. `"volta": { "node": "20.11.0" }`
+#[derive(Clone, Deserialize, Debug, Eq, PartialEq)]
+pub struct PackageJsonVolta {
+    #[serde()]
+    pub node: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, PartialEq)]
+pub struct PackageJsonDevEngines {
+    #[serde(default, deserialize_with = "deserialize_dev_engines")]
+    pub runtime: Vec<DevEngine>,
+}
+
+#[derive(Clone, Deserialize, Debug, Eq, PartialEq)]
+pub struct DevEngine {
+    pub name: String,
+    pub version: Option<String>,
+    #[serde(rename = "onFail", default)]
+    pub on_fail: OnFail,
+}
+
+/// What to do when no installed version matches a `devEngines` entry
+#[derive(Clone, Copy, Deserialize, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFail {
+    Ignore,
+    Warn,
+    #[default]
+    Error,
+    Download,
+}
+
+impl PackageJson {
+    /// The node version the package asks for.
+    ///
+    /// Exact pins from `volta.node` win over `devEngines.runtime`, which wins over the
+    /// `engines.node` range. Versions that don't come from `devEngines` always use
+    /// [`OnFail::Error`].
+    pub fn node_version(&self) -> Result<Option<(VersionSpec, OnFail)>> {
+        if let Some(version) = self.volta.as_ref().and_then(|volta| volta.node.as_ref()) {
+            return Ok(Some((VersionSpec::parse(version)?, OnFail::Error)));
+        }
+
+        let dev_engine = self
+            .dev_engines
+            .iter()
+            .flat_map(|dev_engines| &dev_engines.runtime)
+            .find(|runtime| runtime.name == "node");
+        if let Some(DevEngine {
+            version: Some(version),
+            on_fail,
+            ..
+        }) = dev_engine
+        {
+            return Ok(Some((VersionSpec::parse(version)?, *on_fail)));
+        }
+
+        Ok(self
+            .engines
+            .as_ref()
+            .and_then(|engines| engines.node.clone())
+            .map(|range| (VersionSpec::Range(range), OnFail::Error)))
+    }
+}
+
 impl TryFrom<PathBuf> for PackageJson {
     type Error = anyhow::Error;
 
@@ -29,3 +101,88 @@ impl TryFrom<PathBuf> for PackageJson {
         Ok(package_json)
     }
 }
+
+/// `devEngines.runtime` can be a single object or a list of alternatives
+fn deserialize_dev_engines<'de, D>(deserializer: D) -> Result<Vec<DevEngine>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(DevEngine),
+        Many(Vec<DevEngine>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(runtime) => vec![runtime],
+        OneOrMany::Many(runtimes) => runtimes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use node_semver::Range;
+    use spectral::prelude::*;
+
+    use crate::{
+        files::package_json::{OnFail, PackageJson},
+        version_spec::VersionSpec,
+    };
+
+    fn node_version(json: &str) -> Option<(VersionSpec, OnFail)> {
+        serde_json::from_str::<PackageJson>(json)
+            .unwrap()
+            .node_version()
+            .unwrap()
+    }
+
+    #[test]
+    fn prefers_volta_pin() {
+        let version =
+            node_version(r#"{ "engines": { "node": ">=18" }, "volta": { "node": "20.11.0" } }"#);
+
+        assert_that!(version).is_equal_to(Some((
+            VersionSpec::parse("20.11.0").unwrap(),
+            OnFail::Error,
+        )));
+    }
+
+    #[test]
+    fn reads_dev_engines_runtime() {
+        let version = node_version(
+            r#"{
+                "engines": { "node": ">=18" },
+                "devEngines": { "runtime": { "name": "node", "version": "20.11.0", "onFail": "download" } }
+            }"#,
+        );
+
+        assert_that!(version).is_equal_to(Some((
+            VersionSpec::parse("20.11.0").unwrap(),
+            OnFail::Download,
+        )));
+    }
+
+    #[test]
+    fn finds_node_in_dev_engines_runtime_list() {
+        let version = node_version(
+            r#"{ "devEngines": { "runtime": [{ "name": "bun" }, { "name": "node", "version": "^20" }] } }"#,
+        );
+
+        assert_that!(version)
+            .is_equal_to(Some((VersionSpec::parse("^20").unwrap(), OnFail::Error)));
+    }
+
+    #[test]
+    fn falls_back_to_engines() {
+        let version = node_version(
+            r#"{ "engines": { "node": ">=18" }, "devEngines": { "runtime": { "name": "bun", "version": "1" } } }"#,
+        );
+
+        assert_that!(version).is_equal_to(Some((
+            VersionSpec::Range(Range::parse(">=18").unwrap()),
+            OnFail::Error,
+        )));
+        assert_that!(node_version(r#"{ "name": "foo" }"#)).is_none();
+    }
+}
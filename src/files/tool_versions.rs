@@ -0,0 +1,214 @@
+use std::{fs, path::PathBuf, str::FromStr};
+
+use anyhow::Result;
+
+use crate::version_spec::VersionSpec;
+
+/// Names the node plugin goes by in asdf and compatible tools like mise
+const NODE_TOOL_NAMES: [&str; 2] = ["nodejs", "node"];
+
+/// An asdf `.tool-versions` file, see https://asdf-vm.com/manage/configuration.html#tool-versions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ToolVersions {
+    pub tools: Vec<Tool>,
+}
+
+/// A `<name> <version> [<fallback version>...]` line
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tool {
+    pub name: String,
+    pub versions: Vec<ToolVersion>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ToolVersion {
+    Version(String),
+    /// `ref:<git ref>`, built from source
+    Ref(String),
+    /// `path:<dir>`, a custom build
+    Path(String),
+    /// `system`, whatever is installed outside of the version manager
+    System,
+}
+
+impl From<&str> for ToolVersion {
+    fn from(value: &str) -> Self {
+        if value == "system" {
+            ToolVersion::System
+        } else if let Some(git_ref) = value.strip_prefix("ref:") {
+            ToolVersion::Ref(git_ref.to_string())
+        } else if let Some(path) = value.strip_prefix("path:") {
+            ToolVersion::Path(path.to_string())
+        } else {
+            ToolVersion::Version(value.to_string())
+        }
+    }
+}
+
+impl FromStr for ToolVersions {
+    type Err = anyhow::Error;
+
+    fn from_str(contents: &str) -> Result<Self> {
+        let tools = contents
+            .lines()
+            .map(|line| line.split_once('#').map_or(line, |(before, _)| before))
+            .filter_map(|line| {
+                let mut words = line.split_whitespace();
+                let name = words.next()?;
+
+                Some(Tool {
+                    name: name.to_string(),
+                    versions: words.map(ToolVersion::from).collect(),
+                })
+            })
+            .collect();
+
+        Ok(ToolVersions { tools })
+    }
+}
+
+impl TryFrom<PathBuf> for ToolVersions {
+    type Error = anyhow::Error;
+
+    fn try_from(path: PathBuf) -> Result<Self, anyhow::Error> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+impl ToolVersions {
+    /// The node versions to try in order, or `None` if node isn't listed.
+    ///
+    /// Invalid versions are skipped with a warning, and it fails if none of the listed versions
+    /// can be installed by nvm-rust.
+    pub fn node_versions(&self) -> Result<Option<Vec<VersionSpec>>> {
+        let Some(tool) = self
+            .tools
+            .iter()
+            .find(|tool| NODE_TOOL_NAMES.contains(&tool.name.as_str()))
+        else {
+            return Ok(None);
+        };
+        if tool.versions.is_empty() {
+            return Ok(None);
+        }
+
+        let mut specs = vec![];
+        let mut errors = vec![];
+        for version in &tool.versions {
+            if let ToolVersion::Version(version) = version {
+                match VersionSpec::parse(version) {
+                    Ok(spec) => specs.push(spec),
+                    Err(err) => {
+                        errors.push(err.context(format!("`{version}` is not a valid version")))
+                    },
+                }
+            }
+        }
+
+        if specs.is_empty() && !errors.is_empty() {
+            return Err(errors.remove(0));
+        }
+        for err in errors {
+            eprintln!("⚠️ Skipping a version of `{}`: {err:#}", tool.name);
+        }
+
+        if specs.is_empty() {
+            let unsupported = tool
+                .versions
+                .iter()
+                .map(|version| match version {
+                    ToolVersion::Ref(git_ref) => format!("`ref:{git_ref}`"),
+                    ToolVersion::Path(path) => format!("`path:{path}`"),
+                    _ => "`system`".to_string(),
+                })
+                .collect::<Vec<_>>();
+
+            anyhow::bail!(
+                "`{}` only lists {}, nvm-rust only supports released versions",
+                tool.name,
+                unsupported.join(", ")
+            );
+        }
+
+        Ok(Some(specs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use crate::{
+        files::tool_versions::{Tool, ToolVersion, ToolVersions},
+        version_spec::VersionSpec,
+    };
+
+    #[test]
+    fn parses_asdf_grammar() {
+        let tool_versions: ToolVersions = "# comment\n\
+            ruby\t3.3.0  \n\
+            \n\
+            nodejs   20.11.0 ref:main path:/opt/node system # trailing comment\n"
+            .parse()
+            .unwrap();
+
+        assert_that!(tool_versions.tools).is_equal_to(vec![
+            Tool {
+                name: "ruby".to_string(),
+                versions: vec![ToolVersion::Version("3.3.0".to_string())],
+            },
+            Tool {
+                name: "nodejs".to_string(),
+                versions: vec![
+                    ToolVersion::Version("20.11.0".to_string()),
+                    ToolVersion::Ref("main".to_string()),
+                    ToolVersion::Path("/opt/node".to_string()),
+                    ToolVersion::System,
+                ],
+            },
+        ]);
+    }
+
+    #[test]
+    fn lists_node_versions_in_order() {
+        let tool_versions: ToolVersions = "nodejs 20.11.0 system 18.19.0".parse().unwrap();
+
+        assert_that!(tool_versions.node_versions().unwrap()).is_equal_to(Some(vec![
+            VersionSpec::parse("20.11.0").unwrap(),
+            VersionSpec::parse("18.19.0").unwrap(),
+        ]));
+    }
+
+    #[test]
+    fn skips_invalid_versions() {
+        let tool_versions: ToolVersions = "nodejs 20.11.0 lts/-x 18.19.0".parse().unwrap();
+
+        assert_that!(tool_versions.node_versions().unwrap()).is_equal_to(Some(vec![
+            VersionSpec::parse("20.11.0").unwrap(),
+            VersionSpec::parse("18.19.0").unwrap(),
+        ]));
+    }
+
+    #[test]
+    fn fails_without_valid_versions() {
+        let tool_versions: ToolVersions = "nodejs lts/-x system".parse().unwrap();
+
+        let error = tool_versions.node_versions().unwrap_err().to_string();
+        assert_that!(error).contains("`lts/-x` is not a valid version");
+    }
+
+    #[test]
+    fn reports_unsupported_versions() {
+        let tool_versions: ToolVersions = "nodejs ref:main system".parse().unwrap();
+
+        let error = tool_versions.node_versions().unwrap_err().to_string();
+        assert_that!(error).contains("`ref:main`, `system`");
+    }
+
+    #[test]
+    fn ignores_other_tools() {
+        let tool_versions: ToolVersions = "ruby 3.3.0\nnodejs\n".parse().unwrap();
+
+        assert_that!(tool_versions.node_versions().unwrap()).is_none();
+    }
+}
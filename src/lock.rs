@@ -0,0 +1,78 @@
+use std::{
+    fs::{self, File, TryLockError},
+    io::{Seek, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+
+use crate::Config;
+
+const LOCK_FILE_NAME: &str = ".lock";
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An advisory lock on the installation directory, held by commands modifying it.
+///
+/// The lock is released when this is dropped.
+pub struct InstallDirLock {
+    _file: File,
+}
+
+impl InstallDirLock {
+    pub fn acquire(config: &Config) -> Result<Self> {
+        let path = config.get_dir().join(LOCK_FILE_NAME);
+        let timeout = Duration::from_secs(config.lock_timeout);
+        let started_at = Instant::now();
+        let mut has_printed = false;
+
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .context(format!("Failed to open lock file {path:?}"))?;
+
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) => {
+                    let holder = fs::read_to_string(&path)
+                        .map(|pid| pid.trim().to_string())
+                        .unwrap_or_default();
+                    let holder = if holder.is_empty() {
+                        "another process".to_string()
+                    } else {
+                        format!("PID {holder}")
+                    };
+
+                    if started_at.elapsed() >= timeout {
+                        anyhow::bail!(
+                            "Timed out after {}s waiting for lock held by {holder} ({path:?})",
+                            timeout.as_secs()
+                        );
+                    }
+
+                    if !has_printed {
+                        eprintln!("Waiting for lock held by {holder}...");
+                        has_printed = true;
+                    }
+
+                    thread::sleep(POLL_INTERVAL);
+                },
+                Err(TryLockError::Error(err)) => {
+                    return Err(err).context(format!("Failed to lock {path:?}"))
+                },
+            }
+        }
+
+        // Only used for the waiting message, so failing to write it isn't critical
+        let _ = file
+            .set_len(0)
+            .and_then(|_| file.rewind())
+            .and_then(|_| write!(file, "{}", std::process::id()));
+
+        Ok(InstallDirLock { _file: file })
+    }
+}
@@ -10,16 +10,42 @@ use anyhow::bail;
 use anyhow::Result;
 use clap::{Parser, ValueHint};
 
-use crate::subcommand::{
-    install::InstallCommand, is_installed::IsInstalledCommand, list::ListCommand,
-    parse_version::ParseVersionCommand, switch::SwitchCommand, uninstall::UninstallCommand, Action,
+use crate::{
+    archives::ArchiveFormat,
+    files::{SearchBoundary, VersionSource},
+    lock::InstallDirLock,
+    node_version::InstalledNodeVersion,
+    output::OutputFormat,
+    subcommand::{
+        alias::AliasCommand, cache::CacheCommand, current::CurrentCommand, env::EnvCommand,
+        exec::ExecCommand, info::InfoCommand, install::InstallCommand,
+        is_installed::IsInstalledCommand, keys::KeysCommand, list::ListCommand,
+        parse_version::ParseVersionCommand, run::RunCommand, switch::SwitchCommand,
+        unalias::UnaliasCommand, uninstall::UninstallCommand, version_file::VersionFileCommand,
+        which::WhichCommand, Action,
+    },
 };
 
+mod aliases;
 mod archives;
+mod cache;
+mod checksums;
 mod constants;
+mod download;
 mod files;
+mod lock;
 mod node_version;
+mod output;
+mod release_schedule;
+#[cfg(unix)]
+mod sessions;
+mod shell;
+mod signature;
 mod subcommand;
+mod version_spec;
+
+const DEFAULT_MIRROR: &str = "https://nodejs.org/dist";
+const DEFAULT_RELEASE_KEYS_URL: &str = "https://raw.githubusercontent.com/nodejs/release-keys/HEAD";
 
 #[derive(Parser, Clone, Debug)]
 enum Subcommands {
@@ -29,9 +55,43 @@ enum Subcommands {
     Uninstall(UninstallCommand),
     Use(SwitchCommand),
     ParseVersion(ParseVersionCommand),
+    Info(InfoCommand),
+    Alias(AliasCommand),
+    Unalias(UnaliasCommand),
+    Env(EnvCommand),
+    Cache(CacheCommand),
+    Keys(KeysCommand),
+    VersionFile(VersionFileCommand),
+    Exec(ExecCommand),
+    Run(RunCommand),
+    Current(CurrentCommand),
+    Which(WhichCommand),
 }
 
-#[derive(Parser, Debug)]
+impl Subcommands {
+    /// Whether the command modifies the installation directory, and should hold its lock
+    fn is_mutating(&self) -> bool {
+        match self {
+            Subcommands::Install(_) | Subcommands::Uninstall(_) | Subcommands::Unalias(_) => true,
+            Subcommands::Alias(options) => options.is_mutating(),
+            Subcommands::Cache(options) => options.is_mutating(),
+            Subcommands::Keys(options) => options.is_mutating(),
+            _ => false,
+        }
+    }
+
+    /// Whether the command supports `--output json` and `--output tsv`.
+    ///
+    /// `env` prints a shell script, and `exec` and `run` print whatever the command they run does.
+    fn has_structured_output(&self) -> bool {
+        !matches!(
+            self,
+            Subcommands::Env(_) | Subcommands::Exec(_) | Subcommands::Run(_)
+        )
+    }
+}
+
+#[derive(Parser, Clone, Debug)]
 #[command(
     name = "nvm(-rust)",
     author,
@@ -57,6 +117,102 @@ pub struct Config {
         env("NVM_SHIMS_DIR")
     )]
     shims_dir: Option<PathBuf>,
+    /// Keyring containing the Node.js release keys, used by `install --verify-signature`.
+    ///
+    /// Defaults to `node-release-keys.asc` in the installation directory, or the keys bundled with
+    /// nvm until `nvm keys update` or `nvm keys import` creates it.
+    #[arg(
+        global(true),
+        long,
+        value_hint(ValueHint::FilePath),
+        env("NVM_KEYRING")
+    )]
+    keyring: Option<PathBuf>,
+    /// Mirror to download node versions from
+    #[arg(
+        global(true),
+        long,
+        value_hint(ValueHint::Url),
+        env("NVM_NODEJS_ORG_MIRROR")
+    )]
+    mirror: Option<String>,
+    /// Node.js release schedule, used by `list` to mark versions that reached their end of life.
+    ///
+    /// Not fetched unless set, e.g. to
+    /// https://raw.githubusercontent.com/nodejs/Release/main/schedule.json
+    #[arg(
+        global(true),
+        long,
+        value_hint(ValueHint::Url),
+        env("NVM_RELEASE_SCHEDULE_URL")
+    )]
+    release_schedule_url: Option<String>,
+    /// Repository containing the Node.js release keys, used by `nvm keys update`
+    #[arg(
+        global(true),
+        long,
+        value_hint(ValueHint::Url),
+        env("NVM_RELEASE_KEYS_URL")
+    )]
+    release_keys_url: Option<String>,
+    /// Only use the cached version index and release schedule instead of fetching them
+    #[arg(global(true), long, env("NVM_OFFLINE"))]
+    offline: bool,
+    /// How many seconds the cached version index and release schedule are used before being
+    /// revalidated
+    #[arg(global(true), long, env("NVM_INDEX_TTL"), default_value_t = 3600)]
+    index_ttl: u64,
+    /// How many times to try downloading a version before giving up
+    #[arg(
+        global(true),
+        long,
+        env("NVM_MAX_DOWNLOAD_ATTEMPTS"),
+        default_value_t = 5
+    )]
+    max_download_attempts: u32,
+    /// Milliseconds to wait before retrying a failed download, doubled for every retry
+    #[arg(global(true), long, env("NVM_RETRY_BACKOFF"), default_value_t = 1000)]
+    retry_backoff: u64,
+    /// Which kind of archive to download, `auto` prefers `.tar.xz` when a version provides it
+    #[arg(
+        global(true),
+        long,
+        value_enum,
+        env("NVM_ARCHIVE_FORMAT"),
+        default_value_t = ArchiveFormat::Auto
+    )]
+    archive_format: ArchiveFormat,
+    /// Seconds to wait for other nvm processes to finish modifying the installation directory
+    #[arg(global(true), long, env("NVM_LOCK_TIMEOUT"), default_value_t = 300)]
+    lock_timeout: u64,
+    /// How far up from the current directory to look for version files
+    #[arg(
+        global(true),
+        long,
+        value_enum,
+        env("NVM_VERSION_FILE_BOUNDARY"),
+        default_value_t = SearchBoundary::Git
+    )]
+    version_file_boundary: SearchBoundary,
+    /// Which version files to look for, from highest to lowest precedence
+    #[arg(
+        global(true),
+        long,
+        value_enum,
+        value_delimiter(','),
+        env("NVM_VERSION_FILE_ORDER"),
+        default_values_t = VersionSource::DEFAULT_ORDER
+    )]
+    version_file_order: Vec<VersionSource>,
+    /// How to print the results of commands, see the README for the JSON and TSV schemas
+    #[arg(
+        global(true),
+        long,
+        value_enum,
+        env("NVM_OUTPUT"),
+        default_value_t = OutputFormat::Text
+    )]
+    output: OutputFormat,
     /// Accept any prompts needed for the command to complete
     #[arg(global(true), short, long)]
     force: bool,
@@ -78,6 +234,56 @@ impl Config {
             .map_or_else(|| self.get_dir().join("shims"), |r| r.clone())
     }
 
+    /// Base URL of the node distribution, without a trailing slash
+    pub fn get_mirror(&self) -> String {
+        self.mirror
+            .as_deref()
+            .unwrap_or(DEFAULT_MIRROR)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    pub fn get_release_schedule_url(&self) -> Option<&str> {
+        self.release_schedule_url.as_deref()
+    }
+
+    pub fn get_release_keys_url(&self) -> String {
+        self.release_keys_url
+            .as_deref()
+            .unwrap_or(DEFAULT_RELEASE_KEYS_URL)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Defaults to `node-release-keys.asc` in the installation directory
+    pub fn get_keyring_path(&self) -> PathBuf {
+        self.keyring.as_ref().map_or_else(
+            || self.get_dir().join("node-release-keys.asc"),
+            |r| r.clone(),
+        )
+    }
+
+    /// Whether `--keyring` was passed, in which case the bundled keys are never used
+    pub fn has_custom_keyring(&self) -> bool {
+        self.keyring.is_some()
+    }
+
+    /// Path to directory containing user defined aliases
+    fn get_aliases_dir(&self) -> PathBuf {
+        self.get_dir().join("aliases")
+    }
+
+    /// Path to directory containing the shims symlinks of shell sessions
+    #[cfg(unix)]
+    fn get_sessions_dir(&self) -> PathBuf {
+        self.get_dir().join("sessions")
+    }
+
+    /// Path to directory containing cached downloads
+    fn get_cache_dir(&self) -> PathBuf {
+        self.get_dir().join("cache")
+    }
+
     /// Path to directory containing node versions
     fn get_versions_dir(&self) -> PathBuf {
         self.get_dir().join("versions")
@@ -88,6 +294,19 @@ impl Config {
             force: true,
             dir: Some(self.get_dir()),
             shims_dir: Some(self.get_shims_dir()),
+            keyring: self.keyring.clone(),
+            mirror: Some(self.get_mirror()),
+            release_schedule_url: self.release_schedule_url.clone(),
+            release_keys_url: Some(self.get_release_keys_url()),
+            offline: self.offline,
+            index_ttl: self.index_ttl,
+            max_download_attempts: self.max_download_attempts,
+            retry_backoff: self.retry_backoff,
+            archive_format: self.archive_format,
+            lock_timeout: self.lock_timeout,
+            version_file_boundary: self.version_file_boundary,
+            version_file_order: self.version_file_order.clone(),
+            output: self.output,
             command: self.command.clone(),
         }
     }
@@ -107,7 +326,7 @@ fn ensure_dir_exists(path: &Path) {
     if !path.exists() {
         fs::create_dir_all(path).unwrap_or_else(|err| panic!("Could not create {path:?} - {err}"));
 
-        println!("Created nvm dir at {path:?}");
+        eprintln!("Created nvm dir at {path:?}");
     }
 
     if !path.is_dir() {
@@ -140,6 +359,11 @@ fn ensure_symlinks_work(config: &Config) -> Result<()> {
 
 fn main() -> Result<()> {
     let config: Config = Config::parse();
+    output::init(config.output);
+    if !config.output.is_text() && !config.command.has_structured_output() {
+        anyhow::bail!("This command only supports `--output text`");
+    }
+
     #[cfg(windows)]
     let is_initial_run = !config.get_dir().exists();
 
@@ -152,16 +376,46 @@ fn main() -> Result<()> {
         result?;
     }
 
-    match config.command {
+    let _lock = if config.command.is_mutating() {
+        let lock = InstallDirLock::acquire(&config)?;
+        // Nobody else can be installing right now, so any staging directories are leftovers
+        InstalledNodeVersion::clean_up_staging(&config);
+
+        Some(lock)
+    } else {
+        None
+    };
+
+    let result = match config.command {
         Subcommands::List(ref options) => ListCommand::run(&config, options),
         Subcommands::IsInstalled(ref options) => IsInstalledCommand::run(&config, options),
         Subcommands::Install(ref options) => InstallCommand::run(&config, options),
         Subcommands::Uninstall(ref options) => UninstallCommand::run(&config, options),
         Subcommands::Use(ref options) => SwitchCommand::run(&config, options),
         Subcommands::ParseVersion(ref options) => ParseVersionCommand::run(&config, options),
+        Subcommands::Info(ref options) => InfoCommand::run(&config, options),
+        Subcommands::Alias(ref options) => AliasCommand::run(&config, options),
+        Subcommands::Unalias(ref options) => UnaliasCommand::run(&config, options),
+        Subcommands::Env(ref options) => EnvCommand::run(&config, options),
+        Subcommands::Cache(ref options) => CacheCommand::run(&config, options),
+        Subcommands::Keys(ref options) => KeysCommand::run(&config, options),
+        Subcommands::VersionFile(ref options) => VersionFileCommand::run(&config, options),
+        Subcommands::Exec(ref options) => ExecCommand::run(&config, options),
+        Subcommands::Run(ref options) => RunCommand::run(&config, options),
+        Subcommands::Current(ref options) => CurrentCommand::run(&config, options),
+        Subcommands::Which(ref options) => WhichCommand::run(&config, options),
         #[allow(unreachable_patterns)]
         _ => Ok(()),
+    };
+
+    #[cfg(windows)]
+    if let Some(subcommand::exec::ChildExitCode(code)) =
+        result.as_ref().err().and_then(|err| err.downcast_ref())
+    {
+        std::process::exit(*code);
     }
+
+    result
 }
 
 #[test]
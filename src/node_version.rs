@@ -1,24 +1,35 @@
 use std::{
     cmp::Ordering,
     collections::HashMap,
-    fs::{read_link, remove_dir_all},
+    fmt,
+    fs::{canonicalize, remove_dir_all},
     path::PathBuf,
 };
 
 use anyhow::{Context, Result};
 use node_semver::{Range, Version};
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 use crate::{
-    constants,
-    constants::{ARCH, EXT, PLATFORM, X64},
+    archives::ArchiveFormat,
+    cache, constants,
+    constants::{ARCH, PLATFORM, X64},
+    output::status,
     Config,
 };
 
+const STAGING_PREFIX: &str = ".staging-";
+
 pub trait NodeVersion {
     fn version(&self) -> &Version;
 }
 
+impl<V: NodeVersion> NodeVersion for &V {
+    fn version(&self) -> &Version {
+        (*self).version()
+    }
+}
+
 impl PartialEq<Self> for dyn NodeVersion {
     fn eq(&self, other: &Self) -> bool {
         self.version().eq(other.version())
@@ -29,7 +40,7 @@ impl Eq for dyn NodeVersion {}
 
 impl PartialOrd<Self> for dyn NodeVersion {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.version().cmp(other.version()))
+        Some(self.cmp(other))
     }
 }
 
@@ -86,42 +97,96 @@ pub struct OnlineNodeVersion {
     pub release_date: String,
 
     files: Vec<String>,
+    /// Codename of the LTS line the version belongs to, e.g. `Fermium`
+    #[serde(default, deserialize_with = "deserialize_lts")]
+    pub lts: Option<String>,
+    /// Whether the release contains security fixes
+    #[serde(default)]
+    pub security: bool,
+
+    // Versions of the bundled dependencies, missing for very old releases
+    pub npm: Option<String>,
+    pub v8: Option<String>,
+    pub uv: Option<String>,
+    pub zlib: Option<String>,
+    pub openssl: Option<String>,
+    /// `NODE_MODULE_VERSION`, the ABI version native addons are built against
+    pub modules: Option<String>,
+}
+
+/// `lts` is either the codename of the LTS line or `false`
+fn deserialize_lts<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<String>, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(codename) => Some(codename),
+        _ => None,
+    })
 }
 
 impl OnlineNodeVersion {
-    pub fn fetch_all() -> Result<Vec<Self>> {
-        let response = ureq::get("https://nodejs.org/dist/index.json").call()?;
+    pub fn fetch_all(config: &Config) -> Result<Vec<Self>> {
+        let index = cache::index::fetch(config)?;
 
-        response
-            .into_json()
-            .context("Failed to parse versions list from nodejs.org")
+        serde_json::from_str(&index).context(format!(
+            "Failed to parse versions list from {}",
+            config.get_mirror()
+        ))
     }
 
     pub fn install_path(&self, config: &Config) -> PathBuf {
         config.get_versions_dir().join(self.to_string())
     }
 
-    pub fn download_url(&self) -> String {
-        let file_name: String;
+    pub fn download_url(&self, config: &Config, file_name: &str) -> String {
+        format!("{}/{file_name}", self.dist_url(config))
+    }
+
+    /// URL of the `SHASUMS256.txt` file listing the checksums of this version's archives
+    pub fn shasums_url(&self, config: &Config) -> String {
+        format!("{}/SHASUMS256.txt", self.dist_url(config))
+    }
+
+    /// URL of the detached signature of `SHASUMS256.txt`
+    pub fn shasums_signature_url(&self, config: &Config) -> String {
+        format!("{}/SHASUMS256.txt.sig", self.dist_url(config))
+    }
+
+    /// URL of the clearsigned copy of `SHASUMS256.txt`
+    pub fn shasums_cleartext_url(&self, config: &Config) -> String {
+        format!("{}/SHASUMS256.txt.asc", self.dist_url(config))
+    }
 
+    /// Names of the archives that can be installed on the current platform, most preferred first
+    pub fn file_names(&self, format: ArchiveFormat) -> Vec<String> {
+        format
+            .extensions()
+            .iter()
+            .map(|ext| self.file_name(ext))
+            .collect()
+    }
+
+    fn file_name(&self, ext: &str) -> String {
         #[cfg(target_os = "macos")]
         {
             let has_arm = self.has_arm();
 
-            file_name = self.file(!has_arm);
+            self.file(!has_arm, ext)
         }
 
         #[cfg(not(target_os = "macos"))]
         {
-            file_name = self.file(false);
+            self.file(false, ext)
         }
+    }
 
-        format!("https://nodejs.org/dist/v{}/{}", self.version, file_name)
+    fn dist_url(&self, config: &Config) -> String {
+        format!("{}/v{}", config.get_mirror(), self.version)
     }
 
-    fn file(&self, force_x64: bool) -> String {
+    fn file(&self, force_x64: bool, ext: &str) -> String {
         format!(
-            "node-v{VERSION}-{PLATFORM}-{ARCH}{EXT}",
+            "node-v{VERSION}-{PLATFORM}-{ARCH}{ext}",
             VERSION = self.version(),
             ARCH = if force_x64 { X64 } else { ARCH },
         )
@@ -140,9 +205,9 @@ impl OnlineNodeVersion {
     }
 }
 
-impl ToString for OnlineNodeVersion {
-    fn to_string(&self) -> String {
-        self.version.to_string()
+impl fmt::Display for OnlineNodeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.version)
     }
 }
 
@@ -159,29 +224,41 @@ pub struct InstalledNodeVersion {
 }
 
 impl InstalledNodeVersion {
+    pub fn new(version: Version, path: PathBuf) -> Self {
+        InstalledNodeVersion { version, path }
+    }
+
     // Properties
 
     pub fn get_dir_path(&self, config: &Config) -> PathBuf {
         config.get_versions_dir().join(self.version().to_string())
     }
 
+    /// The directory containing the `node` executable and the globally installed packages
+    pub fn get_bin_path(&self, config: &Config) -> PathBuf {
+        #[cfg(unix)]
+        return self.get_dir_path(config).join("bin");
+        #[cfg(windows)]
+        return self.get_dir_path(config);
+    }
+
     pub fn is_installed(config: &Config, version: &Version) -> bool {
         Self::list(config).iter().any(|v| v.version().eq(version))
     }
 
     pub fn is_selected(&self, config: &Config) -> bool {
-        let path = config.get_shims_dir();
-        let real_path = read_link(path);
-
-        if real_path.is_err() {
+        let Ok(real_path) = canonicalize(config.get_shims_dir()) else {
             return false;
-        }
+        };
 
-        let real_path = real_path.unwrap();
+        canonicalize(self.get_bin_path(config)).is_ok_and(|bin_path| bin_path == real_path)
+    }
 
-        real_path
-            .to_string_lossy()
-            .contains(&self.version().to_string())
+    /// The version the shims point to, if any
+    pub fn get_selected(config: &Config) -> Option<InstalledNodeVersion> {
+        Self::list(config)
+            .into_iter()
+            .find(|version| version.is_selected(config))
     }
 
     // Functions
@@ -189,19 +266,24 @@ impl InstalledNodeVersion {
     pub fn uninstall(self, config: &Config) -> Result<()> {
         remove_dir_all(self.get_dir_path(config))?;
 
-        println!("Uninstalled {}!", self.version());
+        status!("Uninstalled {}!", self.version());
         Ok(())
     }
 
     /// Checks that all the required files are present in the installation dir
-    #[allow(dead_code)]
-    pub fn validate(&self, config: &Config) -> Result<()> {
-        let version_dir =
-            read_link(config.get_shims_dir()).expect("Could not read installation dir");
-
-        let mut required_files = vec![version_dir; 2];
-        required_files[0].set_file_name(format!("node{}", constants::EXEC_EXT));
-        required_files[1].set_file_name(format!("npm{}", constants::EXEC_EXT));
+    pub fn validate(&self) -> Result<()> {
+        #[cfg(unix)]
+        let required_files = [
+            self.path.join("bin").join("node"),
+            self.path
+                .join("bin")
+                .join(format!("npm{}", constants::EXEC_EXT)),
+        ];
+        #[cfg(windows)]
+        let required_files = [
+            self.path.join("node.exe"),
+            self.path.join(format!("npm{}", constants::EXEC_EXT)),
+        ];
 
         if let Some(missing_file) = required_files.iter().find(|file| !file.exists()) {
             anyhow::bail!(
@@ -216,6 +298,38 @@ impl InstalledNodeVersion {
 
     // Static functions
 
+    /// Where a version is extracted to before being moved into place.
+    ///
+    /// Starts with a `.` so it is never listed as an installed version.
+    pub fn staging_path(config: &Config, version: &Version) -> PathBuf {
+        config
+            .get_versions_dir()
+            .join(format!("{STAGING_PREFIX}{version}-{}", std::process::id()))
+    }
+
+    /// Removes staging directories left behind by interrupted installs.
+    ///
+    /// Must only be called while holding the installation directory lock.
+    pub fn clean_up_staging(config: &Config) {
+        let Ok(entries) = config.get_versions_dir().read_dir() else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            if !entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(STAGING_PREFIX)
+            {
+                continue;
+            }
+
+            if let Err(err) = remove_dir_all(entry.path()) {
+                status!("⚠️ Could not clean up {:?}: {err}", entry.path());
+            }
+        }
+    }
+
     pub fn deselect(config: &Config) -> Result<()> {
         remove_dir_all(config.get_shims_dir()).map_err(anyhow::Error::from)
     }
@@ -229,7 +343,7 @@ impl InstalledNodeVersion {
             .expect("Failed to read nvm dir")
         {
             if entry.is_err() {
-                println!("Could not read {entry:?}");
+                status!("Could not read {entry:?}");
                 continue;
             }
 
@@ -257,19 +371,11 @@ impl InstalledNodeVersion {
             })
             .collect()
     }
-
-    /// Returns the latest, installed version matching the version range
-    pub fn find_matching(config: &Config, range: &Range) -> Option<InstalledNodeVersion> {
-        Self::list(config)
-            .iter()
-            .find(|inv| range.satisfies(inv.version()))
-            .map(|inv| inv.to_owned())
-    }
 }
 
-impl ToString for InstalledNodeVersion {
-    fn to_string(&self) -> String {
-        self.version.to_string()
+impl fmt::Display for InstalledNodeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.version)
     }
 }
 
@@ -320,6 +426,14 @@ mod tests {
                     "win-x86-msi".to_string(),
                     "win-x86-zip".to_string(),
                 ],
+                lts: Some("Fermium".to_string()),
+                security: false,
+                npm: Some("6.14.15".to_string()),
+                v8: Some("8.4.371.23".to_string()),
+                uv: Some("1.42.0".to_string()),
+                zlib: Some("1.2.11".to_string()),
+                openssl: Some("1.1.1l".to_string()),
+                modules: Some("83".to_string()),
             };
 
             let json_str = r#"
@@ -365,5 +479,27 @@ mod tests {
 
             Ok(())
         }
+
+        #[test]
+        fn can_parse_non_lts_version_data() -> Result<()> {
+            let json_str = r#"
+{
+    "version": "v15.0.0",
+    "date": "2020-10-20",
+    "files": [],
+    "lts": false,
+    "security": true
+}
+"#
+            .trim();
+
+            let result: OnlineNodeVersion = serde_json::from_str(json_str)?;
+
+            assert_that!(result.lts).is_none();
+            assert_that!(result.security).is_true();
+            assert_that!(result.npm).is_none();
+
+            Ok(())
+        }
     }
 }
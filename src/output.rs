@@ -0,0 +1,134 @@
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// A JSON object, or an array for commands listing things
+    Json,
+    /// Tab separated values with a header row
+    Tsv,
+}
+
+impl OutputFormat {
+    pub fn is_text(self) -> bool {
+        self == OutputFormat::Text
+    }
+}
+
+/// Sets the format used by [`status!`] for the rest of the process
+pub fn init(format: OutputFormat) {
+    FORMAT.get_or_init(|| format);
+}
+
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Prints a progress or status message like `println!`.
+///
+/// The messages go to stderr with machine-readable output, so stdout only contains the result.
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::output::format().is_text() {
+            println!($($arg)*);
+        } else {
+            eprintln!($($arg)*);
+        }
+    };
+}
+pub(crate) use status;
+
+/// A result of a command that can be printed as JSON or TSV
+pub trait Record: Serialize {
+    /// The names of the serialized fields, in the order of the TSV columns
+    const COLUMNS: &'static [&'static str];
+}
+
+/// Prints `records` as a JSON array, or as TSV rows.
+///
+/// Commands print text output themselves, so nothing is printed for [`OutputFormat::Text`].
+pub fn print_records<R: Record>(format: OutputFormat, records: &[R]) -> Result<()> {
+    match format {
+        OutputFormat::Text => {},
+        OutputFormat::Json => println!("{}", serde_json::to_string(records)?),
+        OutputFormat::Tsv => {
+            println!("{}", R::COLUMNS.join("\t"));
+            for record in records {
+                println!("{}", tsv_row(record)?);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Prints `record` as a JSON object, or as a single TSV row
+pub fn print_record<R: Record>(format: OutputFormat, record: &R) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string(record)?);
+            Ok(())
+        },
+        _ => print_records(format, std::slice::from_ref(record)),
+    }
+}
+
+fn tsv_row<R: Record>(record: &R) -> Result<String> {
+    let value = serde_json::to_value(record)?;
+
+    Ok(R::COLUMNS
+        .iter()
+        .map(|column| tsv_value(&value[column]))
+        .join("\t"))
+}
+
+fn tsv_value(value: &Value) -> String {
+    match value {
+        Value::Null => "".to_string(),
+        Value::String(string) => string.replace(['\t', '\n'], " "),
+        Value::Array(values) => values.iter().map(tsv_value).join(","),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use spectral::prelude::*;
+
+    use crate::output::{tsv_row, Record};
+
+    #[derive(Serialize)]
+    struct Entry {
+        version: String,
+        installed: bool,
+        lts: Option<String>,
+        tags: Vec<String>,
+    }
+
+    impl Record for Entry {
+        const COLUMNS: &'static [&'static str] = &["version", "installed", "lts", "tags"];
+    }
+
+    #[test]
+    fn formats_tsv_rows() {
+        let entry = Entry {
+            version: "20.11.0".to_string(),
+            installed: true,
+            lts: None,
+            tags: vec!["a".to_string(), "b\tc".to_string()],
+        };
+
+        assert_that!(tsv_row(&entry).unwrap()).is_equal_to("20.11.0\ttrue\t\ta,b c".to_string());
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+
+use crate::{cache, Config};
+
+/// When a release line stops being supported
+#[derive(Clone, Deserialize, Debug, Eq, PartialEq)]
+struct ReleaseLine {
+    end: NaiveDate,
+}
+
+/// The support windows of the release lines, from https://github.com/nodejs/Release
+#[derive(Clone, Deserialize, Debug, Eq, PartialEq)]
+pub struct ReleaseSchedule(HashMap<String, ReleaseLine>);
+
+impl ReleaseSchedule {
+    /// `None` if no schedule is configured, or it could not be fetched or parsed
+    pub fn fetch(config: &Config) -> Option<Self> {
+        let schedule = cache::schedule::fetch(config)?;
+
+        serde_json::from_str(&schedule)
+            .map_err(|err| eprintln!("⚠️ Failed to parse the release schedule: {err}"))
+            .ok()
+    }
+
+    /// Whether the major has reached its end of life, `None` if the schedule doesn't list it
+    pub fn is_eol(&self, major: u64) -> Option<bool> {
+        self.is_eol_on(major, Utc::now().date_naive())
+    }
+
+    fn is_eol_on(&self, major: u64, date: NaiveDate) -> Option<bool> {
+        self.0
+            .get(&format!("v{major}"))
+            .map(|release_line| release_line.end <= date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use spectral::prelude::*;
+
+    use crate::release_schedule::ReleaseSchedule;
+
+    #[test]
+    fn checks_end_of_life_dates() {
+        let schedule: ReleaseSchedule = serde_json::from_str(
+            r#"{
+                "v0.12": { "start": "2015-02-06", "end": "2016-12-31" },
+                "v16": { "start": "2021-04-20", "lts": "2021-10-26", "end": "2023-09-11", "codename": "Gallium" },
+                "v17": { "start": "2021-10-19", "end": "2022-06-01" }
+            }"#,
+        )
+        .unwrap();
+        let date = NaiveDate::from_ymd_opt(2023, 9, 11).unwrap();
+
+        assert_that!(schedule.is_eol_on(16, date)).is_equal_to(Some(true));
+        assert_that!(schedule.is_eol_on(16, date.pred_opt().unwrap())).is_equal_to(Some(false));
+        assert_that!(schedule.is_eol_on(17, date)).is_equal_to(Some(true));
+        assert_that!(schedule.is_eol_on(18, date)).is_equal_to(None);
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    fs,
+    os::unix::fs::symlink,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{lock::InstallDirLock, Config};
+
+/// Creates a shims symlink for a single shell session, stored as `sessions/<shell pid>_<millis>`.
+///
+/// The link starts out pointing to the same version as the current shims dir.
+pub fn create(config: &Config) -> Result<PathBuf> {
+    let sessions_dir = config.get_sessions_dir();
+    // `use` could be switching the shims while they're read, or another shell cleaning up
+    let _lock = InstallDirLock::acquire(config)?;
+    fs::create_dir_all(&sessions_dir)?;
+
+    clean_up(&sessions_dir);
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    let path = sessions_dir.join(format!("{}_{millis}", shell_pid()));
+
+    if let Ok(target) = fs::read_link(config.get_shims_dir()) {
+        symlink(target, &path).context(format!("Failed to create session symlink at {path:?}"))?;
+    }
+
+    Ok(path)
+}
+
+/// Removes the symlinks of sessions whose shell has exited
+fn clean_up(sessions_dir: &Path) {
+    let Ok(entries) = sessions_dir.read_dir() else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        if is_active(&entry.path()) {
+            continue;
+        }
+
+        if let Err(err) = fs::remove_file(entry.path()) {
+            eprintln!("⚠️ Failed to remove old session {:?}: {err}", entry.path());
+        }
+    }
+}
+
+/// `nvm env` is run directly by the shell, so its parent is the shell the session belongs to
+fn shell_pid() -> u32 {
+    std::os::unix::process::parent_id()
+}
+
+fn is_active(path: &Path) -> bool {
+    let pid = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split('_').next())
+        .and_then(|pid| pid.parse::<libc::pid_t>().ok());
+
+    let Some(pid) = pid else {
+        return false;
+    };
+
+    // Signal 0 only checks whether the process exists
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
@@ -0,0 +1,140 @@
+use std::{env, path::Path};
+
+use clap::ValueEnum;
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Pwsh,
+    Nu,
+}
+
+impl Shell {
+    /// Guesses the shell from the environment of the calling process
+    pub fn detect() -> Option<Shell> {
+        // These are only set for processes started from the shells
+        if env::var_os("NU_VERSION").is_some() {
+            return Some(Shell::Nu);
+        }
+        if cfg!(unix) && env::var_os("PSModulePath").is_some() {
+            return Some(Shell::Pwsh);
+        }
+
+        let shell = env::var("SHELL").ok();
+        let name = shell
+            .as_deref()
+            .and_then(|shell| Path::new(shell).file_stem())
+            .and_then(|name| name.to_str());
+
+        match name {
+            Some("bash") => Some(Shell::Bash),
+            Some("zsh") => Some(Shell::Zsh),
+            Some("fish") => Some(Shell::Fish),
+            Some("pwsh" | "powershell") => Some(Shell::Pwsh),
+            Some("nu") => Some(Shell::Nu),
+            _ if cfg!(windows) => Some(Shell::Pwsh),
+            _ => None,
+        }
+    }
+
+    /// Quotes a string so the shell uses it verbatim
+    pub fn quote(self, value: &str) -> String {
+        match self {
+            Shell::Bash | Shell::Zsh => format!("'{}'", value.replace('\'', r"'\''")),
+            Shell::Fish => format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'")),
+            Shell::Pwsh => format!("'{}'", value.replace('\'', "''")),
+            Shell::Nu => format!("\"{}\"", value.replace('\\', r"\\").replace('"', "\\\"")),
+        }
+    }
+
+    pub fn set_env(self, name: &str, value: &str) -> String {
+        let value = self.quote(value);
+
+        match self {
+            Shell::Bash | Shell::Zsh => format!("export {name}={value}"),
+            Shell::Fish => format!("set -gx {name} {value}"),
+            Shell::Pwsh => format!("$env:{name} = {value}"),
+            Shell::Nu => format!("$env.{name} = {value}"),
+        }
+    }
+
+    pub fn prepend_path(self, path: &str) -> String {
+        let path = self.quote(path);
+
+        match self {
+            Shell::Bash | Shell::Zsh => format!("export PATH={path}:\"$PATH\""),
+            Shell::Fish => format!("set -gx PATH {path} $PATH"),
+            Shell::Pwsh => {
+                format!("$env:PATH = {path} + [IO.Path]::PathSeparator + $env:PATH")
+            },
+            Shell::Nu => format!("$env.PATH = ($env.PATH | prepend {path})"),
+        }
+    }
+
+    /// Runs `command` whenever the working directory changes, and once for the current directory.
+    ///
+    /// Version files can be in any parent directory, so the command is responsible for doing
+    /// nothing when there is nothing to switch to.
+    pub fn use_on_cd(self, command: &str) -> String {
+        match self {
+            Shell::Bash => format!(
+                r#"__nvm_use_on_cd() {{
+  if [ "$__NVM_LAST_DIR" != "$PWD" ]; then
+    __NVM_LAST_DIR="$PWD"
+    {command}
+  fi
+}}
+PROMPT_COMMAND="__nvm_use_on_cd${{PROMPT_COMMAND:+; $PROMPT_COMMAND}}""#
+            ),
+            Shell::Zsh => format!(
+                r#"__nvm_use_on_cd() {{
+  {command}
+}}
+autoload -U add-zsh-hook
+add-zsh-hook chpwd __nvm_use_on_cd
+__nvm_use_on_cd"#
+            ),
+            Shell::Fish => format!(
+                r#"function __nvm_use_on_cd --on-variable PWD
+    {command}
+end
+__nvm_use_on_cd"#
+            ),
+            Shell::Pwsh => format!(
+                r#"function global:__NvmUseOnCd {{
+    {command}
+}}
+function global:__NvmSetLocation {{
+    Set-Location @args
+    __NvmUseOnCd
+}}
+Set-Alias -Scope global -Option AllScope cd __NvmSetLocation
+__NvmUseOnCd"#
+            ),
+            Shell::Nu => format!(
+                r#"$env.config = ($env.config | upsert hooks.env_change.PWD (
+    $env.config.hooks?.env_change?.PWD? | default [] | append {{|before, after|
+        {command}
+    }}
+))"#
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use crate::shell::Shell;
+
+    #[test]
+    fn quotes_values() {
+        assert_that!(Shell::Bash.quote("it's")).is_equal_to(r"'it'\''s'".to_string());
+        assert_that!(Shell::Fish.quote(r"C:\it's")).is_equal_to(r"'C:\\it\'s'".to_string());
+        assert_that!(Shell::Pwsh.quote("it's")).is_equal_to("'it''s'".to_string());
+        assert_that!(Shell::Nu.quote(r#"C:\"x""#)).is_equal_to(r#""C:\\\"x\"""#.to_string());
+    }
+}
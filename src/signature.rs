@@ -0,0 +1,277 @@
+use std::{fs, io::Cursor, path::Path};
+
+use anyhow::{Context, Result};
+use pgp::{types::KeyTrait, Deserializable, SignedPublicKey, StandaloneSignature};
+
+use crate::Config;
+
+const ARMOR_HEADER: &str = "-----BEGIN PGP PUBLIC KEY BLOCK-----";
+const CLEARTEXT_HEADER: &str = "-----BEGIN PGP SIGNED MESSAGE-----";
+const SIGNATURE_HEADER: &str = "-----BEGIN PGP SIGNATURE-----";
+
+/// The Node.js release keys at the time nvm was built, used until the keyring in the installation
+/// directory is created.
+///
+/// Refreshed with `cargo run -- keys update --keyring keys/node-release-keys.asc`.
+const BUNDLED_KEYRING: &[u8] = include_bytes!("../keys/node-release-keys.asc");
+
+/// Reads the keys used to verify signatures.
+///
+/// Falls back to the bundled keys if the keyring in the installation directory hasn't been created.
+pub fn load_keys(config: &Config) -> Result<Vec<SignedPublicKey>> {
+    let path = config.get_keyring_path();
+
+    if path.exists() || config.has_custom_keyring() {
+        return read_keyring(&path);
+    }
+
+    parse_keyring(BUNDLED_KEYRING).context("Failed to parse the bundled Node.js release keys")
+}
+
+/// Reads all public keys from a keyring file.
+///
+/// Supports both binary keyrings and (possibly concatenated) ASCII armored keys.
+pub fn read_keyring(path: &Path) -> Result<Vec<SignedPublicKey>> {
+    if !path.exists() {
+        anyhow::bail!(
+            "Could not find a keyring at {path:?}.
+Run `nvm keys update` to download the Node.js release keys to it, or use --keyring to point to another one."
+        );
+    }
+
+    let bytes = fs::read(path).context(format!("Failed to read keyring at {path:?}"))?;
+    let keys = parse_keyring(&bytes).context(format!("Failed to parse keyring at {path:?}"))?;
+
+    if keys.is_empty() {
+        anyhow::bail!("The keyring at {path:?} does not contain any public keys.");
+    }
+
+    Ok(keys)
+}
+
+/// Writes `keys` to `path` as concatenated ASCII armored keys
+pub fn write_keyring(path: &Path, keys: &[SignedPublicKey]) -> Result<()> {
+    let mut armored = String::new();
+    for key in keys {
+        armored.push_str(&key.to_armored_string(None)?);
+        armored.push('\n');
+    }
+
+    fs::write(path, armored).context(format!("Failed to write keyring to {path:?}"))
+}
+
+pub fn parse_keyring(bytes: &[u8]) -> Result<Vec<SignedPublicKey>> {
+    let text = String::from_utf8_lossy(bytes);
+
+    if !text.trim_start().starts_with(ARMOR_HEADER) {
+        return SignedPublicKey::from_bytes_many(Cursor::new(bytes))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(anyhow::Error::from);
+    }
+
+    let mut keys = vec![];
+    for block in text
+        .split(ARMOR_HEADER)
+        .filter(|block| !block.trim().is_empty())
+    {
+        let armored = format!("{ARMOR_HEADER}{block}");
+        let (parsed, _) = SignedPublicKey::from_armor_many(Cursor::new(armored.as_bytes()))?;
+
+        for key in parsed {
+            keys.push(key?);
+        }
+    }
+
+    Ok(keys)
+}
+
+/// Fingerprint of a key, formatted like `gpg --list-keys` does
+pub fn fingerprint(key: &SignedPublicKey) -> String {
+    key.fingerprint()
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect()
+}
+
+/// The primary user ID of a key, e.g. `Jane Doe <jane@example.com>`
+pub fn user_id(key: &SignedPublicKey) -> String {
+    key.details
+        .users
+        .first()
+        .map(|user| user.id.id().to_string())
+        .unwrap_or_default()
+}
+
+/// Makes sure `signature` is a valid detached signature of `data` made by one of `keys`
+pub fn verify(data: &[u8], signature: &[u8], keys: &[SignedPublicKey]) -> Result<()> {
+    let signature = StandaloneSignature::from_bytes(Cursor::new(signature))
+        .context("Failed to parse signature")?;
+
+    verify_signature(&signature, data, keys)
+}
+
+/// Makes sure `message` is a cleartext signed copy of `text` made by one of `keys`.
+///
+/// Only used for releases that don't provide a detached signature.
+pub fn verify_cleartext(text: &str, message: &str, keys: &[SignedPublicKey]) -> Result<()> {
+    let message = message.replace("\r\n", "\n");
+    let (signed_text, signature) =
+        parse_cleartext(&message).context("Failed to parse cleartext signature")?;
+
+    if canonicalize(&signed_text) != canonicalize(text) {
+        anyhow::bail!("The signed copy of SHASUMS256.txt does not match the downloaded one!");
+    }
+
+    let (signature, _) = StandaloneSignature::from_armor_single(Cursor::new(signature.as_bytes()))
+        .context("Failed to parse signature")?;
+
+    verify_signature(&signature, canonicalize(&signed_text).as_bytes(), keys)
+}
+
+/// Splits a cleartext signed message into the dash-escaped text and the armored signature
+fn parse_cleartext(message: &str) -> Result<(String, String)> {
+    let rest = message
+        .trim_start()
+        .strip_prefix(CLEARTEXT_HEADER)
+        .context("Missing the cleartext header")?;
+    // The armor headers, e.g. `Hash: SHA256`, end with an empty line
+    let (_, rest) = rest.split_once("\n\n").context("Missing the signed text")?;
+    let (text, signature) = rest
+        .split_once(&format!("\n{SIGNATURE_HEADER}"))
+        .context("Missing the signature")?;
+
+    let text = text
+        .lines()
+        .map(|line| line.strip_prefix("- ").unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok((text, format!("{SIGNATURE_HEADER}{signature}")))
+}
+
+/// Signatures of text are made over the lines without trailing whitespace, joined with CRLF
+fn canonicalize(text: &str) -> String {
+    text.lines()
+        .map(|line| line.trim_end_matches([' ', '\t']))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+fn verify_signature(
+    signature: &StandaloneSignature,
+    data: &[u8],
+    keys: &[SignedPublicKey],
+) -> Result<()> {
+    if keys.is_empty() {
+        anyhow::bail!(
+            "There are no keys to verify the signature with.
+Run `nvm keys update` to download the Node.js release keys, or use --keyring to point to a keyring."
+        );
+    }
+
+    let is_valid = keys.iter().any(|key| {
+        signature.verify(key, data).is_ok()
+            || key
+                .public_subkeys
+                .iter()
+                .any(|subkey| signature.verify(subkey, data).is_ok())
+    });
+
+    if !is_valid {
+        anyhow::bail!(
+            "The signature of SHASUMS256.txt was not made by any of the keys in the keyring!"
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use crate::signature::{
+        fingerprint, parse_keyring, user_id, verify, verify_cleartext, BUNDLED_KEYRING,
+    };
+
+    const KEYRING: &[u8] = include_bytes!("../test-data/signature/keyring.asc");
+    const OTHER_KEY: &[u8] = include_bytes!("../test-data/signature/other-key.asc");
+    const SHASUMS: &[u8] = include_bytes!("../test-data/signature/SHASUMS256.txt");
+    const SIGNATURE: &[u8] = include_bytes!("../test-data/signature/SHASUMS256.txt.sig");
+    const CLEARTEXT: &str = include_str!("../test-data/signature/SHASUMS256.txt.asc");
+
+    #[test]
+    fn accepts_valid_signature() {
+        let keys = parse_keyring(KEYRING).unwrap();
+
+        assert_that!(keys).has_length(1);
+        assert_that!(verify(SHASUMS, SIGNATURE, &keys)).is_ok();
+    }
+
+    #[test]
+    fn bundles_release_keys() {
+        assert_that!(parse_keyring(BUNDLED_KEYRING).unwrap().is_empty()).is_false();
+    }
+
+    #[test]
+    fn rejects_tampered_data() {
+        let keys = parse_keyring(KEYRING).unwrap();
+        let tampered = String::from_utf8_lossy(SHASUMS).replace("abc123", "abc124");
+
+        assert_that!(verify(tampered.as_bytes(), SIGNATURE, &keys)).is_err();
+    }
+
+    #[test]
+    fn rejects_signature_of_unknown_key() {
+        let keys = parse_keyring(OTHER_KEY).unwrap();
+
+        assert_that!(verify(SHASUMS, SIGNATURE, &keys)).is_err();
+    }
+
+    #[test]
+    fn rejects_signature_without_keys() {
+        assert_that!(parse_keyring(b"").unwrap()).is_empty();
+        assert_that!(verify(SHASUMS, SIGNATURE, &[])).is_err();
+    }
+
+    #[test]
+    fn parses_concatenated_keys() {
+        let keyring = [KEYRING, b"\n", OTHER_KEY].concat();
+        let keys = parse_keyring(&keyring).unwrap();
+
+        assert_that!(keys.iter().map(user_id).collect::<Vec<_>>()).is_equal_to(vec![
+            "nvm-rust test key <test@example.com>".to_string(),
+            "nvm-rust other key <other@example.com>".to_string(),
+        ]);
+        assert_that!(fingerprint(&keys[0]))
+            .is_equal_to("550998E03A2C189F4AE4158B084645FDAE6E8F73".to_string());
+    }
+
+    #[test]
+    fn accepts_valid_cleartext_signature() {
+        let keys = parse_keyring(KEYRING).unwrap();
+        let shasums = String::from_utf8_lossy(SHASUMS);
+
+        assert_that!(verify_cleartext(&shasums, CLEARTEXT, &keys)).is_ok();
+        assert_that!(verify_cleartext(
+            &shasums,
+            &CLEARTEXT.replace('\n', "\r\n"),
+            &keys
+        ))
+        .is_ok();
+    }
+
+    #[test]
+    fn rejects_cleartext_signature_of_other_text() {
+        let keys = parse_keyring(KEYRING).unwrap();
+        let tampered = String::from_utf8_lossy(SHASUMS).replace("abc123", "abc124");
+
+        assert_that!(verify_cleartext(&tampered, CLEARTEXT, &keys)).is_err();
+        assert_that!(verify_cleartext(
+            &tampered,
+            &CLEARTEXT.replace("abc123", "abc124"),
+            &keys
+        ))
+        .is_err();
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    aliases::Alias,
+    node_version::NodeVersion,
+    output::{self, Record},
+    subcommand::Action,
+    Config,
+};
+
+#[derive(Parser, Clone, Debug)]
+#[command(
+    about = "Create an alias for a version, or list existing aliases",
+    long_about = "Create an alias for a version, or list existing aliases.\n\nThe `default` alias is used by the shell integration when no other version is requested."
+)]
+pub struct AliasCommand {
+    /// Name of the alias, e.g. `work` or `default`. Lists all aliases if left out
+    pub name: Option<String>,
+    /// A semver range or alias like `lts/*` to point the alias to. Prints the alias if left out
+    pub version: Option<String>,
+}
+
+impl AliasCommand {
+    pub fn is_mutating(&self) -> bool {
+        self.version.is_some()
+    }
+}
+
+#[derive(Serialize)]
+struct AliasRecord {
+    name: String,
+    target: String,
+    /// The newest installed version the alias resolves to
+    version: Option<String>,
+}
+
+impl Record for AliasRecord {
+    const COLUMNS: &'static [&'static str] = &["name", "target", "version"];
+}
+
+impl Action<AliasCommand> for AliasCommand {
+    fn run(config: &Config, options: &AliasCommand) -> Result<()> {
+        let aliases = match (&options.name, &options.version) {
+            (Some(name), Some(version)) => vec![Alias::set(config, name, version)?],
+            (Some(name), None) => {
+                vec![Alias::get(config, name).context(format!("Alias `{name}` does not exist"))?]
+            },
+            _ => Alias::list(config),
+        };
+
+        if !config.output.is_text() {
+            let records = aliases
+                .into_iter()
+                .map(|alias| AliasRecord {
+                    version: alias
+                        .spec()
+                        .and_then(|spec| spec.find_installed(config))
+                        .ok()
+                        .and_then(|versions| versions.into_iter().next())
+                        .map(|version| version.to_string()),
+                    name: alias.name,
+                    target: alias.target,
+                })
+                .collect::<Vec<_>>();
+
+            return output::print_records(config.output, &records);
+        }
+
+        let output = aliases
+            .iter()
+            .map(|alias| {
+                format!(
+                    "{} -> {} ({})",
+                    alias.name,
+                    alias.target,
+                    resolved(config, alias)
+                )
+            })
+            .join("\n");
+
+        println!("{output}");
+        Ok(())
+    }
+}
+
+/// Shows which installed version an alias currently points to
+fn resolved(config: &Config, alias: &Alias) -> String {
+    match alias
+        .spec()
+        .and_then(|spec| spec.find_installed(config))
+        .map(|versions| versions.into_iter().next())
+    {
+        Ok(Some(version)) => format!("-> {}", version.version()),
+        Ok(None) => "not installed".to_string(),
+        Err(err) => format!("⚠️ {err}"),
+    }
+}
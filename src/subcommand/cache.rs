@@ -0,0 +1,173 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{
+    cache::downloads::{self, format_size, parse_duration, CachedArchive},
+    output::{self, status, Record},
+    subcommand::Action,
+    Config,
+};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum CacheSubcommands {
+    /// List cached archives
+    #[command(alias = "ls")]
+    List,
+    /// Print the total size of the cached archives
+    Size,
+    /// Remove all cached archives and the cached version index
+    Clean,
+    /// Remove cached archives that have not been used for a while
+    Prune {
+        /// e.g. `30d`, `12h` or `2w`
+        #[arg(long, value_parser = parse_duration)]
+        older_than: Duration,
+    },
+}
+
+#[derive(Parser, Clone, Debug)]
+#[command(about = "Manage the download cache")]
+pub struct CacheCommand {
+    #[command(subcommand)]
+    pub command: CacheSubcommands,
+}
+
+impl CacheCommand {
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self.command,
+            CacheSubcommands::Clean | CacheSubcommands::Prune { .. }
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct CachedArchiveRecord {
+    file_name: String,
+    /// In bytes
+    size: u64,
+    hash: String,
+    /// Days since the archive was last downloaded or used
+    age_days: u64,
+}
+
+impl Record for CachedArchiveRecord {
+    const COLUMNS: &'static [&'static str] = &["file_name", "size", "hash", "age_days"];
+}
+
+impl From<&CachedArchive> for CachedArchiveRecord {
+    fn from(archive: &CachedArchive) -> Self {
+        CachedArchiveRecord {
+            file_name: archive.file_name.clone(),
+            size: archive.size,
+            hash: archive.hash.clone(),
+            age_days: archive.age().as_secs() / SECONDS_PER_DAY,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CacheSizeRecord {
+    /// In bytes
+    size: u64,
+    archives: usize,
+}
+
+impl Record for CacheSizeRecord {
+    const COLUMNS: &'static [&'static str] = &["size", "archives"];
+}
+
+#[derive(Serialize)]
+struct CacheCleanRecord {
+    path: PathBuf,
+}
+
+impl Record for CacheCleanRecord {
+    const COLUMNS: &'static [&'static str] = &["path"];
+}
+
+impl Action<CacheCommand> for CacheCommand {
+    fn run(config: &Config, options: &CacheCommand) -> Result<()> {
+        match &options.command {
+            CacheSubcommands::List => {
+                let archives = downloads::list(config);
+                if !config.output.is_text() {
+                    let records = archives.iter().map(CachedArchiveRecord::from).collect_vec();
+
+                    return output::print_records(config.output, &records);
+                }
+
+                let output = archives
+                    .iter()
+                    .map(|archive| {
+                        format!(
+                            "{} {:>10} {} ({} days ago)",
+                            archive.file_name,
+                            format_size(archive.size),
+                            &archive.hash[..12.min(archive.hash.len())],
+                            archive.age().as_secs() / SECONDS_PER_DAY
+                        )
+                    })
+                    .join("\n");
+
+                println!("{output}");
+                Ok(())
+            },
+            CacheSubcommands::Size => {
+                let archives = downloads::list(config);
+                let total: u64 = archives.iter().map(|archive| archive.size).sum();
+
+                if !config.output.is_text() {
+                    return output::print_record(
+                        config.output,
+                        &CacheSizeRecord {
+                            size: total,
+                            archives: archives.len(),
+                        },
+                    );
+                }
+
+                println!("{} ({} archives)", format_size(total), archives.len());
+                Ok(())
+            },
+            CacheSubcommands::Clean => {
+                let cache_dir = config.get_cache_dir();
+                if cache_dir.exists() {
+                    fs::remove_dir_all(&cache_dir)?;
+                }
+
+                status!("Cleaned the cache at {cache_dir:?}");
+                output::print_record(config.output, &CacheCleanRecord { path: cache_dir })
+            },
+            CacheSubcommands::Prune { older_than } => {
+                let mut removed = vec![];
+
+                for archive in downloads::list(config) {
+                    if archive.age() < *older_than {
+                        continue;
+                    }
+
+                    archive.remove()?;
+                    status!("Removed {}", archive.file_name);
+
+                    removed.push(CachedArchiveRecord::from(&archive));
+                }
+
+                let freed: u64 = removed.iter().map(|archive| archive.size).sum();
+                status!(
+                    "Pruned {} archives, freeing {}",
+                    removed.len(),
+                    format_size(freed)
+                );
+
+                output::print_records(config.output, &removed)
+            },
+        }
+    }
+}
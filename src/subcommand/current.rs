@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{
+    node_version::InstalledNodeVersion,
+    output::{self, OutputFormat, Record},
+    subcommand::Action,
+    Config,
+};
+
+#[derive(Parser, Clone, Debug)]
+#[command(about = "Print the currently selected node version, or `none`")]
+pub struct CurrentCommand {
+    /// Print the version and the path to its bin directory as JSON, same as `--output json`
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct CurrentRecord {
+    version: Option<String>,
+    /// The bin directory of the version
+    path: Option<PathBuf>,
+}
+
+impl Record for CurrentRecord {
+    const COLUMNS: &'static [&'static str] = &["version", "path"];
+}
+
+impl Action<CurrentCommand> for CurrentCommand {
+    fn run(config: &Config, options: &CurrentCommand) -> Result<()> {
+        let selected = InstalledNodeVersion::get_selected(config);
+        let format = if options.json {
+            OutputFormat::Json
+        } else {
+            config.output
+        };
+
+        if format.is_text() {
+            match selected {
+                Some(version) => println!("{version}"),
+                None => println!("none"),
+            }
+
+            return Ok(());
+        }
+
+        output::print_record(
+            format,
+            &CurrentRecord {
+                version: selected.as_ref().map(|version| version.to_string()),
+                path: selected.map(|version| version.get_bin_path(config)),
+            },
+        )
+    }
+}
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+
+#[cfg(unix)]
+use crate::sessions;
+use crate::{shell::Shell, subcommand::Action, Config};
+
+#[derive(Parser, Clone, Debug)]
+#[command(
+    about = "Print the shell setup needed to use nvm",
+    long_about = "Print the shell setup needed to use nvm.\n\nAdd `eval \"$(nvm env)\"` to your .bashrc or .zshrc, `nvm env | source` to your config.fish, `nvm env | Out-String | Invoke-Expression` to your PowerShell profile, or save the output of `nvm env --shell nu` to a file sourced by your config.nu."
+)]
+pub struct EnvCommand {
+    /// Which shell to print the setup for, detected from the environment by default
+    #[arg(long, value_enum)]
+    pub shell: Option<Shell>,
+    /// Switch versions automatically when entering a directory with a version file
+    #[arg(long)]
+    pub use_on_cd: bool,
+    /// Give the shell its own selected version, so `nvm use` doesn't affect other shells.
+    ///
+    /// New sessions start out with the globally selected version. Not supported on Windows.
+    #[arg(long, env("NVM_PER_SESSION"))]
+    pub per_session: bool,
+}
+
+impl Action<EnvCommand> for EnvCommand {
+    fn run(config: &Config, options: &EnvCommand) -> Result<()> {
+        let shell = options.shell.or_else(Shell::detect).context(
+            "Could not detect which shell you are using, please pass it with `--shell`.",
+        )?;
+
+        let dir = config.get_dir();
+        let shims_dir = if options.per_session {
+            if shell == Shell::Nu {
+                anyhow::bail!("Sessions are not supported in Nushell, since the output of `nvm env` is saved and shared between shells.");
+            }
+
+            session_shims_dir(config)?
+        } else {
+            config.get_shims_dir()
+        };
+
+        let mut lines = vec![
+            shell.set_env("NVM_DIR", &dir.to_string_lossy()),
+            shell.set_env("NVM_SHIMS_DIR", &shims_dir.to_string_lossy()),
+            shell.prepend_path(&shims_dir.to_string_lossy()),
+        ];
+        if options.use_on_cd {
+            lines.push(shell.use_on_cd("nvm use --silent-if-unchanged"));
+        }
+
+        println!("{}", lines.join("\n"));
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn session_shims_dir(config: &Config) -> Result<PathBuf> {
+    sessions::create(config)
+}
+
+/// Sessions are named after the shell's PID to clean them up once it exits, but the parent of a
+/// process can't be found without walking the process list on Windows
+#[cfg(windows)]
+fn session_shims_dir(_: &Config) -> Result<PathBuf> {
+    anyhow::bail!("Sessions are not supported on Windows.");
+}
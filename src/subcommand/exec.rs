@@ -0,0 +1,101 @@
+use std::{env, iter, process::Command};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+
+use crate::{
+    files,
+    lock::InstallDirLock,
+    subcommand::{install, Action},
+    version_spec::VersionSpec,
+    Config,
+};
+
+#[derive(Parser, Clone, Debug)]
+#[command(
+    about = "Run a command with a node version without switching to it",
+    long_about = "Run a command with a node version without switching to it.\n\nThe version's bin directory is prepended to PATH for the command only, e.g. `nvm exec 18 -- npm test`."
+)]
+pub struct ExecCommand {
+    /// A semver range or alias like `lts/*`, read from version files if left out.
+    /// The latest installed version matching it will be used
+    #[arg(value_parser = VersionSpec::parse)]
+    pub version: Option<VersionSpec>,
+    /// Offer to install the latest matching version if none is installed
+    #[arg(long, env("NVM_INSTALL_IF_MISSING"))]
+    pub install_if_missing: bool,
+    /// The command to run and its arguments
+    #[arg(last = true, required = true)]
+    pub command: Vec<String>,
+}
+
+/// The exit code of a command that didn't succeed, which nvm exits with as well
+#[cfg(windows)]
+#[derive(Debug)]
+pub struct ChildExitCode(pub i32);
+
+#[cfg(windows)]
+impl std::fmt::Display for ChildExitCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The command exited with code {}", self.0)
+    }
+}
+
+#[cfg(windows)]
+impl std::error::Error for ChildExitCode {}
+
+impl Action<ExecCommand> for ExecCommand {
+    fn run(config: &Config, options: &ExecCommand) -> Result<()> {
+        let version_filter = options
+            .version
+            .clone()
+            .or_else(|| files::get_version_file(config).map(|version_file| version_file.spec))
+            .context(files::NO_VERSION_ERROR)?;
+
+        let mut version = version_filter.find_installed(config)?.into_iter().next();
+        if version.is_none() && options.install_if_missing {
+            // `exec` doesn't modify the installation dir unless it has to install something
+            let _lock = InstallDirLock::acquire(config)?;
+
+            if install::prompt_install(config, &version_filter)? {
+                version = version_filter.find_installed(config)?.into_iter().next();
+            }
+        }
+        let version = version.context(format!(
+            "No version matching `{version_filter}` is installed."
+        ))?;
+
+        let path = env::var_os("PATH").unwrap_or_default();
+        let path = env::join_paths(
+            iter::once(version.get_bin_path(config)).chain(env::split_paths(&path)),
+        )?;
+
+        let mut command = Command::new(&options.command[0]);
+        command.args(&options.command[1..]).env("PATH", path);
+
+        exec(command)
+    }
+}
+
+/// Replaces nvm with the command, so stdin, signals and the exit code go straight to it
+#[cfg(unix)]
+pub fn exec(mut command: Command) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let err = command.exec();
+    Err(anyhow::Error::from(err).context(format!("Failed to run {:?}", command.get_program())))
+}
+
+/// Windows can't replace the running process, so wait for the command and pass on its exit code.
+/// Ctrl+C is sent to every process in the console, so it reaches the command as well.
+#[cfg(windows)]
+pub fn exec(mut command: Command) -> Result<()> {
+    let status = command
+        .status()
+        .context(format!("Failed to run {:?}", command.get_program()))?;
+
+    match status.code() {
+        Some(0) => Ok(()),
+        code => Err(ChildExitCode(code.unwrap_or(1)).into()),
+    }
+}
@@ -0,0 +1,106 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{
+    node_version::OnlineNodeVersion,
+    output::{self, Record},
+    subcommand::Action,
+    version_spec::VersionSpec,
+    Config,
+};
+
+#[derive(Parser, Clone, Debug)]
+#[command(about = "Show details about a released node version")]
+pub struct InfoCommand {
+    /// A semver range or alias like `lts/*`. The latest version matching it will be shown
+    #[arg(value_parser = VersionSpec::parse)]
+    pub version: VersionSpec,
+}
+
+#[derive(Serialize)]
+struct InfoRecord<'a> {
+    version: String,
+    lts: Option<&'a str>,
+    release_date: &'a str,
+    security: bool,
+    npm: Option<&'a str>,
+    v8: Option<&'a str>,
+    uv: Option<&'a str>,
+    zlib: Option<&'a str>,
+    openssl: Option<&'a str>,
+    modules: Option<&'a str>,
+}
+
+impl Record for InfoRecord<'_> {
+    const COLUMNS: &'static [&'static str] = &[
+        "version",
+        "lts",
+        "release_date",
+        "security",
+        "npm",
+        "v8",
+        "uv",
+        "zlib",
+        "openssl",
+        "modules",
+    ];
+}
+
+impl Action<InfoCommand> for InfoCommand {
+    fn run(config: &Config, options: &InfoCommand) -> Result<()> {
+        let online_versions = OnlineNodeVersion::fetch_all(config)?;
+        let version = options
+            .version
+            .resolve(config)?
+            .find_online(&online_versions)
+            .context(format!(
+                "Did not find a version matching `{}`!",
+                options.version
+            ))?;
+
+        if !config.output.is_text() {
+            return output::print_record(
+                config.output,
+                &InfoRecord {
+                    version: version.to_string(),
+                    lts: version.lts.as_deref(),
+                    release_date: &version.release_date,
+                    security: version.security,
+                    npm: version.npm.as_deref(),
+                    v8: version.v8.as_deref(),
+                    uv: version.uv.as_deref(),
+                    zlib: version.zlib.as_deref(),
+                    openssl: version.openssl.as_deref(),
+                    modules: version.modules.as_deref(),
+                },
+            );
+        }
+
+        let lts = match &version.lts {
+            Some(codename) => format!(" ({codename} LTS)"),
+            None => "".to_string(),
+        };
+        println!("{version}{lts}, released {}", version.release_date);
+        if version.security {
+            println!("⚠️ This release contains security fixes");
+        }
+
+        let components = [
+            ("npm", &version.npm),
+            ("V8", &version.v8),
+            ("libuv", &version.uv),
+            ("zlib", &version.zlib),
+            ("OpenSSL", &version.openssl),
+            ("modules", &version.modules),
+        ];
+        for (name, component_version) in components {
+            println!(
+                "{name:<8} {}",
+                component_version.as_deref().unwrap_or("unknown")
+            );
+        }
+
+        Ok(())
+    }
+}
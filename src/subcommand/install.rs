@@ -1,115 +1,387 @@
-use std::{path::Path, time::Duration};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use node_semver::Range;
-use ureq;
+use pgp::SignedPublicKey;
+use serde::Serialize;
+use ureq::Agent;
 
 use crate::{
-    archives, constants, files,
-    node_version::{
-        filter_version_req, parse_range, InstalledNodeVersion, NodeVersion, OnlineNodeVersion,
-    },
-    subcommand::{switch::SwitchCommand, Action},
+    archives::{self, ArchiveFormat},
+    cache::downloads::{self, CachedArchive},
+    checksums, constants, download, files,
+    node_version::{InstalledNodeVersion, NodeVersion, OnlineNodeVersion},
+    output::{self, status, Record},
+    signature,
+    subcommand::{switch, Action},
+    version_spec::VersionSpec,
     Config,
 };
 
 #[derive(Parser, Clone, Debug)]
 #[command(about = "Install a new node version", alias = "i", alias = "add")]
 pub struct InstallCommand {
-    /// A semver range. The latest version matching this range will be installed
-    #[arg(value_parser = parse_range)]
-    pub version: Option<Range>,
+    /// A semver range or alias like `lts/*`. The latest version matching it will be installed
+    #[arg(value_parser = VersionSpec::parse)]
+    pub version: Option<VersionSpec>,
     /// Switch to the new version after installing it
     #[arg(long, short, default_value("false"))]
     pub switch: bool,
     /// Enable corepack after installing the new version
     #[arg(long, default_value("true"), hide(true), env("NVM_ENABLE_COREPACK"))]
     pub enable_corepack: bool,
+    /// Verify the signature of the checksums against the Node.js release keys before installing
+    #[arg(long, env("NVM_VERIFY_SIGNATURE"))]
+    pub verify_signature: bool,
+}
+
+#[derive(Serialize)]
+struct InstallRecord {
+    version: String,
+    path: PathBuf,
+    already_installed: bool,
+    switched: bool,
+}
+
+impl Record for InstallRecord {
+    const COLUMNS: &'static [&'static str] = &["version", "path", "already_installed", "switched"];
 }
 
 impl Action<InstallCommand> for InstallCommand {
     fn run(config: &Config, options: &InstallCommand) -> Result<()> {
-        let version_filter = options
-            .version
-            .clone()
-            .or_else(|| files::get_version_file().map(|version_file| version_file.range()));
+        let record = install(config, options)?;
 
-        if version_filter.is_none() {
-            anyhow::bail!("You did not pass a version and we did not find any version files (package.json#engines, .nvmrc) in the current directory.");
-        }
-        let version_filter = version_filter.unwrap();
+        output::print_record(config.output, &record)
+    }
+}
+
+fn install(config: &Config, options: &InstallCommand) -> Result<InstallRecord> {
+    let version_filter = options.version.clone().or_else(|| {
+        files::get_version_file(config).map(|version_file| {
+            status!(
+                "Using {} from {}",
+                version_file.spec,
+                version_file.path.display()
+            );
+            version_file.spec
+        })
+    });
 
-        let online_versions = OnlineNodeVersion::fetch_all()?;
-        let filtered_versions = filter_version_req(online_versions, &version_filter);
+    if version_filter.is_none() {
+        anyhow::bail!(files::NO_VERSION_ERROR);
+    }
+    let version_filter = version_filter.unwrap().resolve(config)?;
 
-        let version_to_install = filtered_versions.first().context(format!(
+    let online_versions = OnlineNodeVersion::fetch_all(config)?;
+    let version_to_install = version_filter
+        .find_online(&online_versions)
+        .context(format!(
             "Did not find a version matching `{}`!",
             &version_filter
         ))?;
 
-        if !config.force && InstalledNodeVersion::is_installed(config, version_to_install.version())
-        {
-            println!(
-                "{} is already installed - skipping...",
-                version_to_install.version()
-            );
+    let install_path = version_to_install.install_path(config);
+    if !config.force && InstalledNodeVersion::is_installed(config, version_to_install.version()) {
+        status!(
+            "{} is already installed - skipping...",
+            version_to_install.version()
+        );
 
-            return Ok(());
-        }
+        return Ok(InstallRecord {
+            version: version_to_install.to_string(),
+            path: install_path,
+            already_installed: true,
+            switched: false,
+        });
+    }
+
+    download_and_extract_to(config, options, version_to_install, &install_path)?;
 
-        let install_path = version_to_install.install_path(config);
-        download_and_extract_to(version_to_install, &install_path)?;
+    let switched = config.force
+        || (options.switch
+            && dialoguer::Confirm::new()
+                .with_prompt(format!("Switch to {}?", version_to_install))
+                .default(true)
+                .interact()?);
+    if switched {
+        switch::switch_to(config, version_to_install.version())?;
+    }
 
-        if config.force
-            || (options.switch
-                && dialoguer::Confirm::new()
-                    .with_prompt(format!("Switch to {}?", version_to_install.to_string()))
-                    .default(true)
-                    .interact()?)
+    if options.enable_corepack {
+        if let Err(e) = std::process::Command::new(
+            install_path
+                .join("bin")
+                .join(format!("corepack{}", constants::EXEC_EXT)),
+        )
+        .arg("enable")
+        .output()
         {
-            SwitchCommand::run(
-                &config.with_force(),
-                &SwitchCommand {
-                    version: Some(Range::parse(version_to_install.to_string())?),
-                },
-            )?;
+            status!("⚠️ Failed to automatically enable corepack!\n{e}",)
+        }
+    }
+
+    Ok(InstallRecord {
+        version: version_to_install.to_string(),
+        path: install_path,
+        already_installed: false,
+        switched,
+    })
+}
+
+/// Offers to install the latest version matching `version_filter`, returning whether it was
+/// installed
+pub fn prompt_install(config: &Config, version_filter: &VersionSpec) -> Result<bool> {
+    if !config.force
+        && !dialoguer::Confirm::new()
+            .with_prompt(format!(
+                "No version matching `{version_filter}` is installed, install it?"
+            ))
+            .default(true)
+            .interact()?
+    {
+        return Ok(false);
+    }
+
+    // Parsing the options makes sure the defaults from the environment are used
+    let options = InstallCommand::try_parse_from(["install", &version_filter.to_string()])?;
+
+    // Installing with `--force` always switches to the new version, which is left to the caller
+    let config = Config {
+        force: false,
+        ..config.clone()
+    };
+    install(&config, &options)?;
+
+    Ok(true)
+}
+
+fn download_and_extract_to(
+    config: &Config,
+    options: &InstallCommand,
+    version: &OnlineNodeVersion,
+    path: &Path,
+) -> Result<()> {
+    let archive = if config.offline {
+        if options.verify_signature {
+            anyhow::bail!("Signatures can not be verified in offline mode.");
         }
 
-        if options.enable_corepack {
-            if let Err(e) = std::process::Command::new(
-                install_path
-                    .join("bin")
-                    .join(format!("corepack{}", constants::EXEC_EXT)),
-            )
-            .arg("enable")
-            .output()
-            {
-                println!("⚠️ Failed to automatically enable corepack!\n{e}",)
-            }
+        let file_names = version.file_names(config.archive_format);
+        let cached = file_names
+            .iter()
+            .find_map(|file_name| downloads::find_by_name(config, file_name))
+            .context(format!(
+                "{} has not been downloaded before and can not be installed in offline mode.",
+                file_names.join(" or ")
+            ))?;
+        cached.verify()?;
+        cached.mark_used();
+
+        status!("Using cached {}", cached.file_name);
+        cached
+    } else {
+        download(config, options, version)?
+    };
+
+    extract_atomically(&archive.path, version, path, config)
+}
+
+/// Extracts the archive into a staging directory, which is moved into place once it's been
+/// validated, so an interrupted install never leaves a broken version behind.
+fn extract_atomically(
+    archive_path: &Path,
+    version: &OnlineNodeVersion,
+    path: &Path,
+    config: &Config,
+) -> Result<()> {
+    let staging_path = InstalledNodeVersion::staging_path(config, version.version());
+
+    let result = archives::extract_archive(archive_path, &staging_path).and_then(|_| {
+        InstalledNodeVersion::new(version.version().clone(), staging_path.clone()).validate()
+    });
+    if let Err(err) = result {
+        if staging_path.exists() {
+            fs::remove_dir_all(&staging_path)?;
         }
 
-        Ok(())
+        return Err(err);
+    }
+
+    if path.exists() {
+        let mut replaced_path = staging_path.clone().into_os_string();
+        replaced_path.push(".replaced");
+
+        replace_dir(&staging_path, path, Path::new(&replaced_path))?;
+    } else {
+        fs::rename(&staging_path, path)?;
     }
+
+    #[cfg(windows)]
+    status!(
+        "Extracted to {}",
+        // Have to remove \\?\ prefix 🤮
+        path.to_str()
+            .unwrap()
+            .strip_prefix("\\\\?\\")
+            .unwrap_or_else(|| path.to_str().unwrap())
+    );
+    #[cfg(unix)]
+    status!("Extracted to {path:?}");
+
+    Ok(())
+}
+
+/// Moves `new_path` to `path`, which already exists. Directories can't be renamed over, so the
+/// old one is moved to `replaced_path` first, and moved back if the new one can't be moved.
+fn replace_dir(new_path: &Path, path: &Path, replaced_path: &Path) -> Result<()> {
+    fs::rename(path, replaced_path)?;
+
+    if let Err(err) = fs::rename(new_path, path) {
+        fs::rename(replaced_path, path)
+            .context(format!("Failed to restore {path:?} from {replaced_path:?}"))?;
+
+        return Err(err).context(format!("Failed to move {new_path:?} to {path:?}"));
+    }
+
+    // The new version is in place, and leftovers are cleaned up with the other staging dirs
+    if let Err(err) = fs::remove_dir_all(replaced_path) {
+        status!("⚠️ Could not clean up {replaced_path:?}: {err}");
+    }
+
+    Ok(())
 }
 
-fn download_and_extract_to(version: &OnlineNodeVersion, path: &Path) -> Result<()> {
-    let url = version.download_url();
-    let agent = ureq::AgentBuilder::new()
-        .timeout_connect(Duration::from_secs(30))
-        .timeout_read(Duration::from_secs(120))
-        .timeout_write(Duration::from_secs(120))
-        .build();
-
-    println!("Downloading from {url}...");
-    let response = agent
-        .get(&url)
-        .call()
+/// Verifies the detached `SHASUMS256.txt.sig`, falling back to the clearsigned
+/// `SHASUMS256.txt.asc` for releases that don't provide one
+fn verify_shasums_signature(
+    agent: &Agent,
+    retry: &download::RetryPolicy,
+    config: &Config,
+    version: &OnlineNodeVersion,
+    shasums: &str,
+    keys: &[SignedPublicKey],
+) -> Result<()> {
+    let context = || {
+        format!(
+            "Failed to download checksum signature for version: {}",
+            version.version()
+        )
+    };
+
+    match download::download_to_bytes(agent, &version.shasums_signature_url(config), retry) {
+        Ok(signature) => signature::verify(shasums.as_bytes(), &signature, keys),
+        Err(err) if download::is_not_found(&err) => {
+            let message =
+                download::download_to_bytes(agent, &version.shasums_cleartext_url(config), retry)
+                    .with_context(context)?;
+
+            signature::verify_cleartext(shasums, &String::from_utf8_lossy(&message), keys)
+        },
+        Err(err) => Err(err).with_context(context),
+    }
+}
+
+fn download(
+    config: &Config,
+    options: &InstallCommand,
+    version: &OnlineNodeVersion,
+) -> Result<CachedArchive> {
+    let agent = download::agent();
+    let retry = download::RetryPolicy::from(config);
+
+    let shasums = download::download_to_bytes(&agent, &version.shasums_url(config), &retry)
+        .context(format!(
+            "Failed to download checksums for version: {}",
+            version.version()
+        ))?;
+    let shasums = String::from_utf8(shasums).context("SHASUMS256.txt is not valid UTF-8")?;
+
+    if options.verify_signature {
+        let keys = signature::load_keys(config)?;
+
+        verify_shasums_signature(&agent, &retry, config, version, &shasums, &keys)?;
+        status!("Verified signature of SHASUMS256.txt");
+    }
+
+    // Not every version has every kind of archive, e.g. old ones only have .tar.gz
+    let file_names = version.file_names(config.archive_format);
+    let file_name = match file_names
+        .iter()
+        .find(|file_name| checksums::find_hash(&shasums, file_name).is_some())
+    {
+        Some(file_name) => file_name,
+        None if config.archive_format == ArchiveFormat::Auto => file_names.last().unwrap(),
+        None => anyhow::bail!(
+            "Version {} does not provide {}",
+            version.version(),
+            file_names.join(" or ")
+        ),
+    };
+
+    if let Some(cached) = checksums::find_hash(&shasums, file_name)
+        .and_then(|hash| downloads::find(config, file_name, hash))
+    {
+        match cached.is_intact() {
+            Ok(true) => {
+                cached.mark_used();
+                status!("Using cached {file_name}");
+                return Ok(cached);
+            },
+            Ok(false) => {
+                status!(
+                    "⚠️ Cached archive {:?} is corrupted, downloading it again",
+                    cached.path
+                );
+                cached.remove()?;
+            },
+            Err(err) => {
+                status!("⚠️ Could not read cached {file_name}, downloading it again: {err}")
+            },
+        }
+    }
+
+    let url = version.download_url(config, file_name);
+    status!("Downloading from {url}...");
+    // Partial downloads are kept so they can be resumed by the next run
+    let partial_path = downloads::partial_path(config, file_name)?;
+    let hash = download::download_to_file(&agent, &url, &partial_path, &retry)
         .context(format!("Failed to download version: {}", version.version()))?;
 
-    let length: usize = response.header("Content-Length").unwrap().parse()?;
-    let mut bytes: Vec<u8> = Vec::with_capacity(length);
-    response.into_reader().read_to_end(&mut bytes)?;
+    if let Err(err) = checksums::verify(&hash, file_name, &shasums) {
+        fs::remove_file(&partial_path)?;
+        return Err(err);
+    }
+    status!("Verified checksum of {file_name}");
 
-    archives::extract_archive(bytes, path)
+    downloads::store(config, file_name, &hash, &partial_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use spectral::prelude::*;
+
+    use crate::subcommand::install::replace_dir;
+
+    #[test]
+    fn restores_replaced_dir_when_moving_new_one_fails() {
+        let dir = std::env::temp_dir().join(format!("nvm-replace-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("14.6.0");
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("node"), "old").unwrap();
+
+        // The new directory doesn't exist, so moving it into place fails
+        let result = replace_dir(&dir.join("missing"), &path, &dir.join("14.6.0.replaced"));
+
+        assert_that!(result).is_err();
+        assert_that!(fs::read_to_string(path.join("node")).unwrap()).is_equal_to("old".to_string());
+        assert_that!(dir.join("14.6.0.replaced").exists()).is_false();
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
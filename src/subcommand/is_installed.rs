@@ -1,11 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
-use node_semver::Range;
+use serde::Serialize;
 
 use crate::{
     files,
-    node_version::{parse_range, InstalledNodeVersion, NodeVersion},
+    output::{self, status, Record},
     subcommand::Action,
+    version_spec::VersionSpec,
     Config,
 };
 
@@ -16,9 +17,9 @@ use crate::{
     alias = "installed"
 )]
 pub struct IsInstalledCommand {
-    /// A semver range. Will be matched against installed all installed versions.
-    #[arg(value_parser = parse_range)]
-    pub version: Option<Range>,
+    /// A semver range or alias like `lts/*`. Will be matched against all installed versions.
+    #[arg(value_parser = VersionSpec::parse)]
+    pub version: Option<VersionSpec>,
     /// Which exit code to use when a version is not installed.
     #[arg(long, short = 'e', default_value = "1")]
     pub exit_code: i32,
@@ -27,35 +28,62 @@ pub struct IsInstalledCommand {
     pub quiet: bool,
 }
 
+#[derive(Serialize)]
+struct IsInstalledRecord {
+    requested: String,
+    installed: bool,
+    /// The newest installed version matching the request
+    version: Option<String>,
+}
+
+impl Record for IsInstalledRecord {
+    const COLUMNS: &'static [&'static str] = &["requested", "installed", "version"];
+}
+
 impl Action<IsInstalledCommand> for IsInstalledCommand {
     fn run(config: &Config, options: &IsInstalledCommand) -> Result<()> {
-        let version_filter = options
-            .version
-            .clone()
-            .or_else(|| files::get_version_file().map(|version_file| version_file.range()));
+        let version_filter = options.version.clone().or_else(|| {
+            files::get_version_file(config).map(|version_file| {
+                if !options.quiet {
+                    status!(
+                        "Using {} from {}",
+                        version_file.spec,
+                        version_file.path.display()
+                    );
+                }
+                version_file.spec
+            })
+        });
 
         if version_filter.is_none() {
-            anyhow::bail!("You did not pass a version and we did not find any version files (package.json#engines, .nvmrc) in the current directory.");
+            anyhow::bail!(files::NO_VERSION_ERROR);
         }
         let version_filter = version_filter.unwrap();
 
-        let installed_versions = InstalledNodeVersion::list(config);
-        for installed_version in installed_versions {
-            if !version_filter.satisfies(installed_version.version()) {
-                continue;
-            }
+        let installed_version = version_filter.find_installed(config)?.into_iter().next();
 
-            if !options.quiet {
-                println!(
-                    "✅ A version matching {version_filter} is installed ({})!",
-                    installed_version.to_string()
-                );
+        if !config.output.is_text() {
+            output::print_record(
+                config.output,
+                &IsInstalledRecord {
+                    requested: version_filter.to_string(),
+                    installed: installed_version.is_some(),
+                    version: installed_version
+                        .as_ref()
+                        .map(|version| version.to_string()),
+                },
+            )?;
+        } else if !options.quiet {
+            match &installed_version {
+                Some(version) => {
+                    println!("✅ A version matching {version_filter} is installed ({version})!")
+                },
+                None => println!("❌ A version matching {version_filter} is not installed."),
             }
-            return Ok(());
         }
 
-        if !options.quiet {
-            println!("❌ A version matching {version_filter} is not installed.");
+        if installed_version.is_some() {
+            return Ok(());
         }
 
         std::process::exit(options.exit_code)
@@ -0,0 +1,169 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueHint};
+use itertools::Itertools;
+use pgp::SignedPublicKey;
+use serde::Serialize;
+
+use crate::{
+    download,
+    output::{self, status, Record},
+    signature,
+    subcommand::Action,
+    Config,
+};
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum KeysSubcommands {
+    /// List the keys signatures are verified with
+    #[command(alias = "ls")]
+    List,
+    /// Replace the keyring with the current Node.js release keys
+    Update,
+    /// Add the keys in a file to the keyring
+    Import {
+        /// A binary or ASCII armored keyring, e.g. from `gpg --export --armor`
+        #[arg(value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+    },
+}
+
+#[derive(Parser, Clone, Debug)]
+#[command(about = "Manage the keys used by `install --verify-signature`")]
+pub struct KeysCommand {
+    #[command(subcommand)]
+    pub command: KeysSubcommands,
+}
+
+impl KeysCommand {
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self.command,
+            KeysSubcommands::Update | KeysSubcommands::Import { .. }
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct KeyRecord {
+    fingerprint: String,
+    user_id: String,
+}
+
+impl Record for KeyRecord {
+    const COLUMNS: &'static [&'static str] = &["fingerprint", "user_id"];
+}
+
+impl From<&SignedPublicKey> for KeyRecord {
+    fn from(key: &SignedPublicKey) -> Self {
+        KeyRecord {
+            fingerprint: signature::fingerprint(key),
+            user_id: signature::user_id(key),
+        }
+    }
+}
+
+fn print_keys(config: &Config, keys: &[SignedPublicKey]) -> Result<()> {
+    if !config.output.is_text() {
+        let records = keys.iter().map(KeyRecord::from).collect_vec();
+
+        return output::print_records(config.output, &records);
+    }
+
+    for key in keys {
+        println!(
+            "{} {}",
+            signature::fingerprint(key),
+            signature::user_id(key)
+        );
+    }
+
+    Ok(())
+}
+
+/// Downloads the keys listed in `keys.list` of the release keys repository
+fn fetch_release_keys(config: &Config) -> Result<Vec<SignedPublicKey>> {
+    let agent = download::agent();
+    let retry = download::RetryPolicy::from(config);
+    let url = config.get_release_keys_url();
+
+    let list = download::download_to_bytes(&agent, &format!("{url}/keys.list"), &retry)
+        .context("Failed to download the list of release keys")?;
+    let fingerprints = String::from_utf8_lossy(&list)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_uppercase)
+        .collect_vec();
+
+    let mut keys = vec![];
+    for fingerprint in fingerprints {
+        let bytes =
+            download::download_to_bytes(&agent, &format!("{url}/keys/{fingerprint}.asc"), &retry)
+                .context(format!("Failed to download release key {fingerprint}"))?;
+
+        let key = signature::parse_keyring(&bytes)
+            .context(format!("Failed to parse release key {fingerprint}"))?
+            .into_iter()
+            .find(|key| signature::fingerprint(key) == fingerprint)
+            .context(format!(
+                "The downloaded release key does not match its fingerprint {fingerprint}"
+            ))?;
+
+        keys.push(key);
+    }
+
+    if keys.is_empty() {
+        anyhow::bail!("{url}/keys.list does not list any keys.");
+    }
+
+    Ok(keys)
+}
+
+impl Action<KeysCommand> for KeysCommand {
+    fn run(config: &Config, options: &KeysCommand) -> Result<()> {
+        match &options.command {
+            KeysSubcommands::List => {
+                let keys = signature::load_keys(config)?;
+                if keys.is_empty() {
+                    eprintln!("There are no keys, run `nvm keys update` to download them.");
+                }
+
+                print_keys(config, &keys)
+            },
+            KeysSubcommands::Update => {
+                let path = config.get_keyring_path();
+                let keys = fetch_release_keys(config)?;
+
+                signature::write_keyring(&path, &keys)?;
+                status!("Saved {} release keys to {path:?}", keys.len());
+
+                print_keys(config, &keys)
+            },
+            KeysSubcommands::Import { file } => {
+                let path = config.get_keyring_path();
+                let imported = signature::read_keyring(file)?;
+
+                // The default keyring starts out with the bundled keys, a custom one from scratch
+                let mut keys = if path.exists() || !config.has_custom_keyring() {
+                    signature::load_keys(config)?
+                } else {
+                    vec![]
+                };
+                let fingerprints = keys.iter().map(signature::fingerprint).collect_vec();
+                keys.extend(
+                    imported
+                        .iter()
+                        .filter(|key| !fingerprints.contains(&signature::fingerprint(key)))
+                        .cloned(),
+                );
+
+                signature::write_keyring(&path, &keys)?;
+                status!("Imported {} keys into {path:?}", imported.len());
+
+                print_keys(config, &imported)
+            },
+        }
+    }
+}
@@ -1,15 +1,23 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use clap::Parser;
 use itertools::Itertools;
-use node_semver::Range;
+use serde::Serialize;
 
 use crate::{
     node_version,
-    node_version::{parse_range, InstalledNodeVersion, NodeVersion, OnlineNodeVersion},
+    node_version::{InstalledNodeVersion, NodeVersion, OnlineNodeVersion},
+    output::{self, Record},
+    release_schedule::ReleaseSchedule,
     subcommand::Action,
+    version_spec::VersionSpec,
     Config,
 };
 
+/// How many majors without installed versions are shown by default
+const DEFAULT_MAJORS: usize = 5;
+
 enum VersionStatus<'p> {
     Latest,
     NotInstalled,
@@ -38,25 +46,105 @@ impl<'p> VersionStatus<'p> {
         }
     }
 
+    fn to_name(&self) -> &'static str {
+        match self {
+            VersionStatus::Latest => "latest",
+            VersionStatus::NotInstalled => "not-installed",
+            VersionStatus::Outdated(_) => "outdated",
+        }
+    }
+
     fn to_version_string(&self) -> String {
         match self {
-            VersionStatus::Outdated(version) => format!("-> {}", version.to_string()),
+            VersionStatus::Outdated(version) => format!(" -> {version}"),
             _ => "".to_string(),
         }
     }
 }
 
+/// e.g. ` (Erbium LTS, security)`
+fn release_tags(version: &OnlineNodeVersion) -> String {
+    let tags = version
+        .lts
+        .iter()
+        .map(|codename| format!("{codename} LTS"))
+        .chain(version.security.then(|| "security".to_string()))
+        .join(", ");
+
+    if tags.is_empty() {
+        tags
+    } else {
+        format!(" ({tags})")
+    }
+}
+
+/// e.g. ` [selected, EOL]`
+fn markers(selected: bool, eol: bool) -> String {
+    let markers = [(selected, "selected"), (eol, "EOL")]
+        .into_iter()
+        .filter_map(|(is_set, marker)| is_set.then_some(marker))
+        .join(", ");
+
+    if markers.is_empty() {
+        markers
+    } else {
+        format!(" [{markers}]")
+    }
+}
+
+/// A line of the list, showing the newest installed version of a major or the newest release
+#[derive(Serialize)]
+struct ListEntry {
+    version: String,
+    installed: bool,
+    selected: bool,
+    /// Codename of the LTS line the newest release of the major belongs to
+    lts: Option<String>,
+    security: Option<bool>,
+    /// The newest release of the major, not known when only listing installed versions
+    latest_in_major: Option<String>,
+    /// `latest`, `outdated` or `not-installed`
+    status: Option<&'static str>,
+    /// Whether the major has reached its end of life, not known when only listing installed
+    /// versions or without a release schedule
+    eol: Option<bool>,
+}
+
+impl Record for ListEntry {
+    const COLUMNS: &'static [&'static str] = &[
+        "version",
+        "installed",
+        "selected",
+        "lts",
+        "security",
+        "latest_in_major",
+        "status",
+        "eol",
+    ];
+}
+
 #[derive(Parser, Clone, Debug)]
 #[command(about = "List installed and released node versions", alias = "ls")]
 pub struct ListCommand {
     /// Only display installed versions
-    #[arg(short, long, alias = "installed")]
+    #[arg(short, long, alias = "installed", conflicts_with_all = ["remote", "lts"])]
     pub local: bool,
-    /// Filter by semantic versions.
+    /// Display every release instead of the newest of each major
+    #[arg(short, long)]
+    pub remote: bool,
+    /// Only display releases of LTS lines
+    #[arg(long)]
+    pub lts: bool,
+    /// How many of the newest majors to display, majors with installed versions are always shown.
+    ///
+    /// Defaults to 5, or all majors with `--remote`.
+    #[arg(long)]
+    pub majors: Option<usize>,
+    /// Filter by semantic versions or aliases.
     ///
-    /// `12`, `^10.9`, `>=8.10`, `>=8, <9`
-    #[arg(short('F'), long, value_parser = parse_range)]
-    pub filter: Option<Range>,
+    /// `12`, `^10.9`, `>=8.10`, `>=8, <9`, `lts/*`, `lts/iron`
+    #[arg(short('F'), long, value_parser = VersionSpec::parse)]
+    pub filter: Option<VersionSpec>,
 }
 
 impl Action<ListCommand> for ListCommand {
@@ -65,7 +153,25 @@ impl Action<ListCommand> for ListCommand {
 
         // Use filter option if it was passed
         if let Some(filter) = &options.filter {
-            installed_versions = node_version::filter_version_req(installed_versions, filter);
+            installed_versions = filter.find_installed(config)?;
+        }
+
+        if options.local && !config.output.is_text() {
+            let entries = installed_versions
+                .iter()
+                .map(|version| ListEntry {
+                    version: version.to_string(),
+                    installed: true,
+                    selected: version.is_selected(config),
+                    lts: None,
+                    security: None,
+                    latest_in_major: None,
+                    status: None,
+                    eol: None,
+                })
+                .collect::<Vec<_>>();
+
+            return output::print_records(config.output, &entries);
         }
 
         if options.local {
@@ -73,16 +179,28 @@ impl Action<ListCommand> for ListCommand {
                 "{}",
                 installed_versions
                     .iter()
-                    .map(|version| version.to_string())
+                    .map(|version| format!(
+                        "{version}{}",
+                        markers(version.is_selected(config), false)
+                    ))
                     .join("\n")
             );
 
             return Ok(());
         }
 
+        let mut online_versions = OnlineNodeVersion::fetch_all(config)?;
+        let schedule = ReleaseSchedule::fetch(config);
+        if options.lts {
+            online_versions.retain(|version| version.lts.is_some());
+        }
+
+        if options.remote {
+            return list_remote(config, options, &online_versions, schedule.as_ref());
+        }
+
         // Get available versions, extract only the latest for each major version
         let mut latest_per_major = Vec::<&OnlineNodeVersion>::new();
-        let online_versions = OnlineNodeVersion::fetch_all()?;
         if !online_versions.is_empty() {
             latest_per_major = node_version::get_latest_of_each_major(&online_versions);
             latest_per_major.sort();
@@ -105,29 +223,62 @@ impl Action<ListCommand> for ListCommand {
 
         // Show the latest X major versions by default
         // and show any older, installed versions as well
+        let majors = options.majors.unwrap_or(DEFAULT_MAJORS);
         let mut versions_to_show = Vec::<(&OnlineNodeVersion, &Vec<&InstalledNodeVersion>)>::new();
         for (i, (latest, installed)) in majors_and_installed_versions.iter().enumerate() {
-            if i < 5 || !installed.is_empty() {
+            if i < majors || !installed.is_empty() {
                 versions_to_show.push((latest, installed));
             }
         }
 
-        let output = versions_to_show
+        let entries = versions_to_show
             .iter()
             .map(|(online_version, installed_versions)| {
                 let version_status = VersionStatus::from(installed_versions, online_version);
+                // Show the selected version of the major, or else the newest installed one
+                let installed_version = installed_versions
+                    .iter()
+                    .find(|version| version.is_selected(config))
+                    .or(installed_versions.first());
 
-                let version_to_show = if installed_versions.is_empty() {
-                    online_version.to_string()
-                } else {
-                    installed_versions[0].to_string()
+                let entry = ListEntry {
+                    version: installed_version
+                        .map(|version| version.to_string())
+                        .unwrap_or_else(|| online_version.to_string()),
+                    installed: installed_version.is_some(),
+                    selected: installed_version.is_some_and(|version| version.is_selected(config)),
+                    lts: online_version.lts.clone(),
+                    security: Some(online_version.security),
+                    latest_in_major: Some(online_version.to_string()),
+                    status: Some(version_status.to_name()),
+                    eol: schedule
+                        .as_ref()
+                        .and_then(|schedule| schedule.is_eol(online_version.version().major)),
                 };
 
+                (version_status, entry, *online_version)
+            })
+            .collect::<Vec<_>>();
+
+        if !config.output.is_text() {
+            let entries = entries
+                .into_iter()
+                .map(|(_, entry, _)| entry)
+                .collect::<Vec<_>>();
+
+            return output::print_records(config.output, &entries);
+        }
+
+        let output = entries
+            .iter()
+            .map(|(version_status, entry, online_version)| {
                 format!(
-                    "{} {} {}",
+                    "{} {}{}{}{}",
                     &version_status.to_emoji(),
-                    version_to_show,
+                    entry.version,
                     &version_status.to_version_string(),
+                    release_tags(online_version),
+                    markers(entry.selected, entry.eol == Some(true)),
                 )
             })
             .join("\n");
@@ -136,3 +287,94 @@ impl Action<ListCommand> for ListCommand {
         Ok(())
     }
 }
+
+/// Lists every release matching the filter, newest first
+fn list_remote(
+    config: &Config,
+    options: &ListCommand,
+    online_versions: &[OnlineNodeVersion],
+    schedule: Option<&ReleaseSchedule>,
+) -> Result<()> {
+    let mut versions: Vec<&OnlineNodeVersion> = match &options.filter {
+        Some(filter) => filter
+            .resolve(config)?
+            .filter(online_versions.iter().collect(), online_versions),
+        None => online_versions.iter().collect(),
+    };
+    versions.sort();
+    versions.reverse();
+
+    if let Some(majors) = options.majors {
+        let shown_majors = versions
+            .iter()
+            .map(|version| version.version().major)
+            .dedup()
+            .take(majors)
+            .collect::<Vec<_>>();
+
+        versions.retain(|version| shown_majors.contains(&version.version().major));
+    }
+
+    let installed_versions = InstalledNodeVersion::list(config);
+    let latest_per_major = node_version::get_latest_of_each_major(online_versions)
+        .into_iter()
+        .map(|latest| (latest.version().major, latest))
+        .collect::<HashMap<_, _>>();
+
+    let entries = versions
+        .into_iter()
+        .map(|online_version| {
+            let installed_version = installed_versions
+                .iter()
+                .find(|installed| installed.version() == online_version.version());
+            let latest = latest_per_major.get(&online_version.version().major);
+            let status = match (installed_version, latest) {
+                (None, _) => VersionStatus::NotInstalled,
+                (Some(_), Some(latest)) if latest.version() > online_version.version() => {
+                    VersionStatus::Outdated(latest)
+                },
+                (Some(_), _) => VersionStatus::Latest,
+            };
+
+            (
+                online_version,
+                ListEntry {
+                    version: online_version.to_string(),
+                    installed: installed_version.is_some(),
+                    selected: installed_version.is_some_and(|version| version.is_selected(config)),
+                    lts: online_version.lts.clone(),
+                    security: Some(online_version.security),
+                    latest_in_major: latest.map(|latest| latest.to_string()),
+                    status: Some(status.to_name()),
+                    eol: schedule
+                        .and_then(|schedule| schedule.is_eol(online_version.version().major)),
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if !config.output.is_text() {
+        let entries = entries
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .collect::<Vec<_>>();
+
+        return output::print_records(config.output, &entries);
+    }
+
+    let output = entries
+        .iter()
+        .map(|(online_version, entry)| {
+            format!(
+                "{} {}{}{}",
+                if entry.installed { '✅' } else { '〰' },
+                entry.version,
+                release_tags(online_version),
+                markers(entry.selected, entry.eol == Some(true)),
+            )
+        })
+        .join("\n");
+
+    println!("{output}");
+    Ok(())
+}
@@ -2,12 +2,23 @@ use anyhow::Result;
 
 use crate::Config;
 
+pub mod alias;
+pub mod cache;
+pub mod current;
+pub mod env;
+pub mod exec;
+pub mod info;
 pub mod install;
 pub mod is_installed;
+pub mod keys;
 pub mod list;
 pub mod parse_version;
+pub mod run;
 pub mod switch;
+pub mod unalias;
 pub mod uninstall;
+pub mod version_file;
+pub mod which;
 
 pub trait Action<T: clap::Parser> {
     fn run(config: &Config, options: &T) -> Result<()>;
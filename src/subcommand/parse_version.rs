@@ -1,8 +1,17 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
 use clap::Parser;
-use node_semver::Range;
+use serde::Serialize;
 
-use crate::{files, node_version::parse_range, subcommand::Action, Config};
+use crate::{
+    files,
+    node_version::OnlineNodeVersion,
+    output::{self, Record},
+    subcommand::Action,
+    version_spec::VersionSpec,
+    Config,
+};
 
 #[derive(Parser, Clone, Debug)]
 #[command(
@@ -11,18 +20,43 @@ use crate::{files, node_version::parse_range, subcommand::Action, Config};
     hide(true)
 )]
 pub struct ParseVersionCommand {
-    /// The semver range to echo the parsed result of
-    #[arg(value_parser = parse_range)]
+    /// The semver range or alias to echo the parsed result of
     pub version: Option<String>,
 }
 
+#[derive(Serialize)]
+struct ParseVersionRecord {
+    /// The passed version string, `null` when it was read from a version file
+    input: Option<String>,
+    version_file: Option<PathBuf>,
+    parsed: String,
+    /// The release an alias currently points to
+    version: Option<String>,
+}
+
+impl Record for ParseVersionRecord {
+    const COLUMNS: &'static [&'static str] = &["input", "version_file", "parsed", "version"];
+}
+
 impl Action<ParseVersionCommand> for ParseVersionCommand {
-    fn run(_: &Config, options: &ParseVersionCommand) -> Result<()> {
+    fn run(config: &Config, options: &ParseVersionCommand) -> Result<()> {
         let version = options.version.clone();
 
         if version.is_none() {
-            if let Some(version_from_files) = files::get_version_file() {
-                println!("{}", version_from_files.range());
+            if let Some(version_from_files) = files::get_version_file(config) {
+                if !config.output.is_text() {
+                    return output::print_record(
+                        config.output,
+                        &ParseVersionRecord {
+                            input: None,
+                            parsed: version_from_files.spec.to_string(),
+                            version: None,
+                            version_file: Some(version_from_files.path),
+                        },
+                    );
+                }
+
+                println!("{}", version_from_files.spec);
 
                 return Ok(());
             }
@@ -33,21 +67,54 @@ impl Action<ParseVersionCommand> for ParseVersionCommand {
         }
         let version = version.unwrap();
 
-        match Range::parse(&version) {
-            Ok(result) => {
+        match VersionSpec::parse(&version) {
+            Ok(spec) => {
+                let release = resolve_alias(config, &spec);
+
+                if !config.output.is_text() {
+                    return output::print_record(
+                        config.output,
+                        &ParseVersionRecord {
+                            input: Some(version),
+                            parsed: spec.to_string(),
+                            version: release.map(|release| release.to_string()),
+                            version_file: None,
+                        },
+                    );
+                }
+
+                let result = match release {
+                    Some(release) => format!("{spec} ({release})"),
+                    None => spec.to_string(),
+                };
+
                 println!(
                     "{:^pad$}\n{:^pad$}\n{}",
                     version,
                     "⬇",
                     result,
-                    pad = result.to_string().len()
+                    pad = result.len()
                 );
                 Ok(())
             },
+            Err(err) if !config.output.is_text() => anyhow::bail!("Failed to parse `{err}`"),
             Err(err) => {
-                println!("Failed to parse `{}`", err.input());
+                println!("Failed to parse `{err}`");
                 Ok(())
             },
         }
     }
 }
+
+/// The release an alias currently points to, shown together with the alias
+fn resolve_alias(config: &Config, spec: &VersionSpec) -> Option<OnlineNodeVersion> {
+    if let VersionSpec::Range(_) = spec {
+        return None;
+    }
+
+    let resolved = spec.resolve(config).ok()?;
+
+    OnlineNodeVersion::fetch_all(config)
+        .ok()
+        .and_then(|versions| resolved.find_online(&versions).cloned())
+}
@@ -0,0 +1,90 @@
+use std::{env, path::Path, process::Command};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+
+use crate::{
+    aliases::Alias,
+    files,
+    subcommand::{exec::exec, switch, Action},
+    version_spec::VersionSpec,
+    Config,
+};
+
+#[derive(Parser, Clone, Debug)]
+#[command(
+    about = "Run node with a version without switching to it",
+    long_about = "Run node with a version without switching to it.\n\nThe first argument is used as the version if it is one and not an existing file, e.g. `nvm run 18 script.js`. Otherwise the version is chosen like `nvm use` does."
+)]
+pub struct RunCommand {
+    /// Print which version is used to stderr
+    #[arg(long)]
+    pub verbose: bool,
+    /// An optional version followed by the arguments passed to node
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+impl Action<RunCommand> for RunCommand {
+    fn run(config: &Config, options: &RunCommand) -> Result<()> {
+        let (version, args) = match options.args.split_first() {
+            Some((first, rest)) if is_version(config, first) => {
+                (Some(VersionSpec::parse(first)?), rest)
+            },
+            _ => (None, options.args.as_slice()),
+        };
+
+        let version_file = version
+            .is_none()
+            .then(|| files::get_version_file(config))
+            .flatten();
+        let version_filter =
+            switch::requested_version(config, version.as_ref(), version_file.as_ref())
+                .context(files::NO_VERSION_OR_DEFAULT_ERROR)?;
+
+        let version = version_filter
+            .find_installed(config)?
+            .into_iter()
+            .next()
+            .context(format!(
+                "No version matching `{version_filter}` is installed."
+            ))?;
+
+        if options.verbose {
+            match &version_file {
+                Some(version_file) => eprintln!(
+                    "Running node {version} ({} from {})",
+                    version_file.spec,
+                    version_file.path.display()
+                ),
+                None => eprintln!("Running node {version}"),
+            }
+        }
+
+        let mut command = Command::new(
+            version
+                .get_bin_path(config)
+                .join(format!("node{}", env::consts::EXE_SUFFIX)),
+        );
+        command.args(args);
+
+        exec(command)
+    }
+}
+
+/// Whether an argument is a version rather than the first argument for node, like a script.
+///
+/// Existing files are always scripts, and ranges need a number, so e.g. `x` isn't read as the
+/// range matching every version.
+fn is_version(config: &Config, arg: &str) -> bool {
+    if Path::new(arg).exists() {
+        return false;
+    }
+
+    match VersionSpec::parse(arg) {
+        Ok(VersionSpec::Alias(name)) => Alias::get(config, &name).is_some(),
+        Ok(VersionSpec::Range(_)) => arg.chars().any(|char| char.is_ascii_digit()),
+        Ok(_) => true,
+        Err(_) => false,
+    }
+}
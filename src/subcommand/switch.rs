@@ -1,4 +1,3 @@
-use std::fs::read_link;
 #[cfg(windows)]
 use std::fs::remove_dir;
 #[cfg(unix)]
@@ -7,58 +6,184 @@ use std::fs::remove_file;
 use std::os::unix::fs::symlink;
 #[cfg(windows)]
 use std::os::windows::fs::symlink_dir;
+use std::{fs::read_link, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use node_semver::{Range, Version};
+use node_semver::Version;
+use serde::Serialize;
 
 use crate::{
-    files,
-    node_version::{parse_range, InstalledNodeVersion, NodeVersion},
-    subcommand::Action,
+    aliases::{Alias, DEFAULT_ALIAS},
+    files::{self, package_json::OnFail, VersionFile},
+    lock::InstallDirLock,
+    node_version::{InstalledNodeVersion, NodeVersion},
+    output::{self, status, Record},
+    subcommand::{install, Action},
+    version_spec::VersionSpec,
     Config,
 };
 
 #[derive(Parser, Clone, Debug)]
 #[command(about = "Switch to an installed node version", alias = "switch")]
 pub struct SwitchCommand {
-    /// A semver range. The latest version matching this range will be switched to.
-    #[arg(value_parser = parse_range)]
-    pub version: Option<Range>,
+    /// A semver range or alias like `lts/*`. The latest version matching it will be switched to.
+    #[arg(value_parser = VersionSpec::parse)]
+    pub version: Option<VersionSpec>,
+    /// Don't print anything if the selected version already matches, and don't fall back to the
+    /// `default` alias when no version was passed and no version file was found.
+    ///
+    /// Used by the shell integration when changing directories.
+    #[arg(long)]
+    pub silent_if_unchanged: bool,
+    /// Offer to install the latest matching version if none is installed
+    #[arg(long, env("NVM_INSTALL_IF_MISSING"))]
+    pub install_if_missing: bool,
+}
+
+#[derive(Serialize)]
+struct UseRecord {
+    /// The selected version after running the command
+    version: Option<String>,
+    /// The version file the version was read from
+    version_file: Option<PathBuf>,
+    /// Whether the command switched versions, instead of keeping the selected one
+    switched: bool,
+}
+
+impl Record for UseRecord {
+    const COLUMNS: &'static [&'static str] = &["version", "version_file", "switched"];
+}
+
+/// Prints the record for when the selected version is kept
+fn print_unchanged(config: &Config, version_file: Option<VersionFile>) -> Result<()> {
+    output::print_record(
+        config.output,
+        &UseRecord {
+            version: InstalledNodeVersion::get_selected(config).map(|version| version.to_string()),
+            version_file: version_file.map(|file| file.path),
+            switched: false,
+        },
+    )
 }
 
 impl Action<SwitchCommand> for SwitchCommand {
     fn run(config: &Config, options: &SwitchCommand) -> Result<()> {
-        let version_filter = options
-            .clone()
+        let version_file = options
             .version
-            .or_else(|| files::get_version_file().map(|version_file| version_file.range()));
+            .is_none()
+            .then(|| files::get_version_file(config))
+            .flatten();
+        // Entering a directory without a version file shouldn't undo a manual `nvm use`
+        if options.silent_if_unchanged && options.version.is_none() && version_file.is_none() {
+            return print_unchanged(config, None);
+        }
+
+        let version_filter =
+            requested_version(config, options.version.as_ref(), version_file.as_ref());
 
         if version_filter.is_none() {
-            anyhow::bail!("You did not pass a version and we did not find any version files (package.json#engines, .nvmrc) in the current directory.");
+            anyhow::bail!(files::NO_VERSION_OR_DEFAULT_ERROR);
         }
         let version_filter = version_filter.unwrap();
 
-        let version = InstalledNodeVersion::find_matching(config, &version_filter);
-        if version.is_none() {
-            anyhow::bail!("No version matching the version range was found.")
+        let mut installed_versions = version_filter.find_installed(config)?;
+        if options.silent_if_unchanged
+            && installed_versions
+                .iter()
+                .any(|version| version.is_selected(config))
+        {
+            return print_unchanged(config, version_file);
         }
 
-        let version = version.unwrap();
-
-        if !InstalledNodeVersion::is_installed(config, version.version()) {
-            anyhow::bail!("{} is not installed", version.to_string());
+        if let Some(version_file) = &version_file {
+            status!(
+                "Using {} from {}",
+                version_file.spec,
+                version_file.path.display()
+            );
         }
 
-        let result = set_shims(config, version.version());
-        if let Ok(()) = result {
-            println!("Switched to {}", version.to_string());
+        // `use` runs on every `cd` with the shell integration, so it only waits for other nvm
+        // processes once it knows it has to modify the installation dir
+        let mut lock = None;
+        if installed_versions.is_empty() {
+            let on_fail = version_file
+                .as_ref()
+                .map(|file| file.on_fail)
+                .unwrap_or_default();
+
+            let installed = match on_fail {
+                OnFail::Ignore => false,
+                OnFail::Warn => {
+                    eprintln!("⚠️ No installed version matches `{version_filter}`, keeping the current version");
+                    false
+                },
+                OnFail::Download => {
+                    lock = Some(InstallDirLock::acquire(config)?);
+                    install::prompt_install(&config.with_force(), &version_filter)?
+                },
+                OnFail::Error if options.install_if_missing => {
+                    lock = Some(InstallDirLock::acquire(config)?);
+                    install::prompt_install(config, &version_filter)?
+                },
+                OnFail::Error => {
+                    anyhow::bail!("No version matching the version range was found.")
+                },
+            };
+            if !installed {
+                return print_unchanged(config, version_file);
+            }
+
+            installed_versions = version_filter.find_installed(config)?;
         }
 
-        result
+        let version = installed_versions
+            .into_iter()
+            .next()
+            .context("No version matching the version range was found.")?;
+        let _lock = match lock {
+            Some(lock) => lock,
+            None => InstallDirLock::acquire(config)?,
+        };
+        switch_to(config, version.version())?;
+
+        output::print_record(
+            config.output,
+            &UseRecord {
+                version: Some(version.to_string()),
+                version_file: version_file.map(|file| file.path),
+                switched: true,
+            },
+        )
     }
 }
 
+/// Points the shims to `version`
+pub fn switch_to(config: &Config, version: &Version) -> Result<()> {
+    if !InstalledNodeVersion::is_installed(config, version) {
+        anyhow::bail!("{version} is not installed");
+    }
+
+    set_shims(config, version)?;
+    status!("Switched to {version}");
+
+    Ok(())
+}
+
+/// The version passed by the user, or else the one from the version file, or else the `default`
+/// alias
+pub fn requested_version(
+    config: &Config,
+    version: Option<&VersionSpec>,
+    version_file: Option<&VersionFile>,
+) -> Option<VersionSpec> {
+    version
+        .or(version_file.map(|file| &file.spec))
+        .cloned()
+        .or_else(|| Alias::get(config, DEFAULT_ALIAS).map(|alias| VersionSpec::Alias(alias.name)))
+}
+
 #[cfg(windows)]
 fn set_shims(config: &Config, version: &Version) -> Result<()> {
     let shims_dir = config.get_shims_dir();
@@ -0,0 +1,44 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{
+    aliases::Alias,
+    output::{self, status, Record},
+    subcommand::Action,
+    Config,
+};
+
+#[derive(Parser, Clone, Debug)]
+#[command(about = "Remove an alias")]
+pub struct UnaliasCommand {
+    /// Name of the alias to remove
+    pub name: String,
+}
+
+#[derive(Serialize)]
+struct UnaliasRecord {
+    name: String,
+    /// What the alias pointed to
+    target: String,
+}
+
+impl Record for UnaliasRecord {
+    const COLUMNS: &'static [&'static str] = &["name", "target"];
+}
+
+impl Action<UnaliasCommand> for UnaliasCommand {
+    fn run(config: &Config, options: &UnaliasCommand) -> Result<()> {
+        let alias = Alias::get(config, &options.name)
+            .context(format!("Alias `{}` does not exist", options.name))?;
+
+        let record = UnaliasRecord {
+            name: alias.name.clone(),
+            target: alias.target.clone(),
+        };
+        alias.remove(config)?;
+        status!("Removed alias `{}`", options.name);
+
+        output::print_record(config.output, &record)
+    }
+}
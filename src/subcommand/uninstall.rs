@@ -1,10 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
-use node_semver::Range;
+use serde::Serialize;
 
 use crate::{
-    node_version::{parse_range, InstalledNodeVersion, NodeVersion},
+    node_version::{InstalledNodeVersion, NodeVersion},
+    output::{self, status, Record},
     subcommand::Action,
+    version_spec::VersionSpec,
     Config,
 };
 
@@ -16,21 +18,30 @@ use crate::{
     alias = "remove"
 )]
 pub struct UninstallCommand {
-    /// A semver range. The latest version matching this range will be installed
-    #[arg(value_parser = parse_range)]
-    pub version: Range,
+    /// A semver range or alias. The latest installed version matching it will be uninstalled
+    #[arg(value_parser = VersionSpec::parse)]
+    pub version: VersionSpec,
+}
+
+#[derive(Serialize)]
+struct UninstallRecord {
+    version: String,
+}
+
+impl Record for UninstallRecord {
+    const COLUMNS: &'static [&'static str] = &["version"];
 }
 
 impl Action<UninstallCommand> for UninstallCommand {
     fn run(config: &Config, options: &UninstallCommand) -> Result<()> {
-        let version = InstalledNodeVersion::find_matching(config, &options.version);
+        let version = options.version.find_installed(config)?.into_iter().next();
         if version.is_none() {
             anyhow::bail!("{} is not installed.", &options.version.to_string())
         }
 
         let version = version.unwrap();
         if version.is_selected(config) {
-            println!("{} is currently selected.", version.version());
+            status!("{} is currently selected.", version.version());
 
             if !config.force
                 && !(dialoguer::Confirm::new()
@@ -43,6 +54,11 @@ impl Action<UninstallCommand> for UninstallCommand {
             InstalledNodeVersion::deselect(config)?;
         }
 
-        version.uninstall(config)
+        let record = UninstallRecord {
+            version: version.to_string(),
+        };
+        version.uninstall(config)?;
+
+        output::print_record(config.output, &record)
     }
 }
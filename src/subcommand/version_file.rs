@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::{
+    files,
+    output::{self, status, Record},
+    subcommand::Action,
+    Config,
+};
+
+#[derive(Parser, Clone, Debug)]
+#[command(
+    about = "Explain which version file is used in the current directory",
+    alias = "which-file"
+)]
+pub struct VersionFileCommand {}
+
+#[derive(Serialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum FileStatus {
+    /// The file with the highest precedence specifying a version
+    Used,
+    /// Specifies a version, but a file with higher precedence is used
+    Ignored,
+    /// Doesn't specify a version
+    Skipped,
+    /// Failed to parse
+    Failed,
+}
+
+#[derive(Serialize)]
+struct VersionFileRecord {
+    path: PathBuf,
+    /// The kind of file, e.g. `package.json` or `.nvmrc`
+    source: &'static str,
+    status: FileStatus,
+    version: Option<String>,
+    /// Why the file was skipped or failed to parse
+    reason: Option<String>,
+}
+
+impl Record for VersionFileRecord {
+    const COLUMNS: &'static [&'static str] = &["path", "source", "status", "version", "reason"];
+}
+
+impl Action<VersionFileCommand> for VersionFileCommand {
+    fn run(config: &Config, _: &VersionFileCommand) -> Result<()> {
+        let search_dirs = files::search_dirs(config);
+        if let (Some(first), Some(last)) = (search_dirs.first(), search_dirs.last()) {
+            status!("Searched from {} up to {}", first.display(), last.display());
+        }
+        let order = config
+            .version_file_order
+            .iter()
+            .map(|source| source.file_name())
+            .collect::<Vec<_>>();
+        status!("Precedence: {}\n", order.join(", "));
+
+        let mut used = false;
+        let mut records = vec![];
+        for candidate in files::candidates(config) {
+            let (status, version, reason) = match candidate.spec {
+                Ok(Some(spec)) if !used => {
+                    used = true;
+                    (FileStatus::Used, Some(spec.to_string()), None)
+                },
+                Ok(Some(spec)) => (FileStatus::Ignored, Some(spec.to_string()), None),
+                Ok(None) => (
+                    FileStatus::Skipped,
+                    None,
+                    Some(candidate.source.missing_reason().to_string()),
+                ),
+                Err(err) => (FileStatus::Failed, None, Some(err.to_string())),
+            };
+
+            records.push(VersionFileRecord {
+                path: candidate.path,
+                source: candidate.source.file_name(),
+                status,
+                version,
+                reason,
+            });
+        }
+
+        if !config.output.is_text() {
+            return output::print_records(config.output, &records);
+        }
+
+        for record in &records {
+            let path = record.path.display();
+            let version = record.version.as_deref().unwrap_or_default();
+            let reason = record.reason.as_deref().unwrap_or_default();
+
+            match record.status {
+                FileStatus::Used => println!("✅ {path}: using {version}"),
+                FileStatus::Ignored => {
+                    println!("⏭️ {path}: ignored {version}, a file with higher precedence is used")
+                },
+                FileStatus::Skipped => println!("⏭️ {path}: skipped, {reason}"),
+                FileStatus::Failed => println!("❌ {path}: skipped, failed to parse: {reason}"),
+            }
+        }
+
+        if !used {
+            println!("No version file specifying a node version was found");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use std::{env, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+
+use crate::{
+    constants, files,
+    output::{self, OutputFormat, Record},
+    subcommand::{switch, Action},
+    version_spec::VersionSpec,
+    Config,
+};
+
+#[derive(ValueEnum, Serialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Binary {
+    Node,
+    Npm,
+    Npx,
+    Corepack,
+}
+
+impl Binary {
+    fn file_name(self) -> String {
+        match self {
+            Binary::Node => format!("node{}", env::consts::EXE_SUFFIX),
+            Binary::Npm => format!("npm{}", constants::EXEC_EXT),
+            Binary::Npx => format!("npx{}", constants::EXEC_EXT),
+            Binary::Corepack => format!("corepack{}", constants::EXEC_EXT),
+        }
+    }
+}
+
+#[derive(Parser, Clone, Debug)]
+#[command(about = "Print the path to a binary of an installed node version")]
+pub struct WhichCommand {
+    /// A semver range or alias like `lts/*`, chosen like `use` does if left out.
+    /// The latest installed version matching it will be used
+    #[arg(value_parser = VersionSpec::parse)]
+    pub version: Option<VersionSpec>,
+    /// Which of the version's binaries to print the path of
+    #[arg(long, value_enum, default_value_t = Binary::Node)]
+    pub bin: Binary,
+    /// Print the version, binary and path as JSON, same as `--output json`
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct WhichRecord {
+    version: String,
+    bin: Binary,
+    path: PathBuf,
+}
+
+impl Record for WhichRecord {
+    const COLUMNS: &'static [&'static str] = &["version", "bin", "path"];
+}
+
+impl Action<WhichCommand> for WhichCommand {
+    fn run(config: &Config, options: &WhichCommand) -> Result<()> {
+        let version_file = options
+            .version
+            .is_none()
+            .then(|| files::get_version_file(config))
+            .flatten();
+        let version_filter =
+            switch::requested_version(config, options.version.as_ref(), version_file.as_ref())
+                .context(files::NO_VERSION_OR_DEFAULT_ERROR)?;
+
+        let version = version_filter
+            .find_installed(config)?
+            .into_iter()
+            .next()
+            .context(format!(
+                "No version matching `{version_filter}` is installed."
+            ))?;
+
+        let path = version.get_bin_path(config).join(options.bin.file_name());
+        if !path.exists() {
+            anyhow::bail!("{version} does not include {:?}", options.bin.file_name());
+        }
+
+        let format = if options.json {
+            OutputFormat::Json
+        } else {
+            config.output
+        };
+        if format.is_text() {
+            println!("{}", path.display());
+
+            return Ok(());
+        }
+
+        output::print_record(
+            format,
+            &WhichRecord {
+                version: version.to_string(),
+                bin: options.bin,
+                path,
+            },
+        )
+    }
+}
@@ -0,0 +1,246 @@
+use std::{collections::HashSet, fmt};
+
+use anyhow::{Context, Result};
+use node_semver::{Range, Version};
+
+use crate::{
+    aliases::{self, Alias},
+    node_version::{
+        filter_version_req, parse_range, InstalledNodeVersion, NodeVersion, OnlineNodeVersion,
+    },
+    Config,
+};
+
+/// A version requested by the user, either a semver range or an alias like `lts/*`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VersionSpec {
+    Range(Range),
+    /// `latest`, `current` or `node`
+    Latest,
+    /// `lts/*`
+    LatestLts,
+    /// `lts/-N`, the LTS line N lines before the newest one
+    LtsOffset(usize),
+    /// `lts/<codename>`, e.g. `lts/iron`
+    LtsCodename(String),
+    /// A user defined alias, which has to be resolved before matching versions
+    Alias(String),
+}
+
+/// How many aliases pointing to other aliases are followed before giving up
+const MAX_ALIAS_DEPTH: usize = 16;
+
+impl VersionSpec {
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let lowercase = value.to_lowercase();
+
+        let spec = match lowercase.as_str() {
+            "latest" | "current" | "node" => VersionSpec::Latest,
+            "lts" | "lts/*" => VersionSpec::LatestLts,
+            _ => match lowercase.strip_prefix("lts/") {
+                Some(offset) if offset.starts_with('-') => {
+                    let offset: usize = offset[1..].parse().context(format!(
+                        "`{value}` is not a valid LTS offset, e.g. `lts/-1`"
+                    ))?;
+
+                    if offset == 0 {
+                        VersionSpec::LatestLts
+                    } else {
+                        VersionSpec::LtsOffset(offset)
+                    }
+                },
+                Some(codename) if !codename.is_empty() => {
+                    VersionSpec::LtsCodename(codename.to_string())
+                },
+                _ => match parse_range(value) {
+                    Ok(range) => VersionSpec::Range(range),
+                    Err(_) if aliases::is_alias_name(value) => {
+                        VersionSpec::Alias(value.to_string())
+                    },
+                    Err(err) => return Err(err),
+                },
+            },
+        };
+
+        Ok(spec)
+    }
+
+    /// Follows aliases until reaching a spec that can be matched against versions
+    pub fn resolve(&self, config: &Config) -> Result<VersionSpec> {
+        let mut spec = self.clone();
+
+        for _ in 0..MAX_ALIAS_DEPTH {
+            let VersionSpec::Alias(name) = &spec else {
+                return Ok(spec);
+            };
+
+            let alias = Alias::get(config, name)
+                .context(format!("`{name}` is neither a version nor an alias"))?;
+            spec = alias.spec()?;
+        }
+
+        anyhow::bail!("`{self}` points to too many other aliases, is one of them circular?")
+    }
+
+    /// Whether the version index is needed to tell which versions match the spec
+    pub fn needs_index(&self) -> bool {
+        matches!(
+            self,
+            VersionSpec::LatestLts | VersionSpec::LtsOffset(_) | VersionSpec::LtsCodename(_)
+        )
+    }
+
+    /// Returns the versions matching the spec, keeping their order.
+    ///
+    /// `index` is used to look up which LTS line a version belongs to, and aliases have to be
+    /// resolved first.
+    pub fn filter<V: NodeVersion>(&self, versions: Vec<V>, index: &[OnlineNodeVersion]) -> Vec<V> {
+        match self {
+            VersionSpec::Range(range) => filter_version_req(versions, range),
+            VersionSpec::Latest => versions,
+            VersionSpec::Alias(_) => vec![],
+            _ => {
+                let Some(codename) = self.lts_codename(index) else {
+                    return vec![];
+                };
+                let line: HashSet<&Version> = index
+                    .iter()
+                    .filter(|version| version.lts.as_ref() == Some(&codename))
+                    .map(|version| version.version())
+                    .collect();
+
+                versions
+                    .into_iter()
+                    .filter(|version| line.contains(version.version()))
+                    .collect()
+            },
+        }
+    }
+
+    /// Returns the newest released version matching the spec
+    pub fn find_online<'v>(&self, index: &'v [OnlineNodeVersion]) -> Option<&'v OnlineNodeVersion> {
+        self.filter(index.iter().collect(), index)
+            .into_iter()
+            .max_by(|a, b| a.version().cmp(b.version()))
+    }
+
+    /// Returns the installed versions matching the spec, newest first
+    pub fn find_installed(&self, config: &Config) -> Result<Vec<InstalledNodeVersion>> {
+        let spec = self.resolve(config)?;
+        let index = if spec.needs_index() {
+            OnlineNodeVersion::fetch_all(config)?
+        } else {
+            vec![]
+        };
+
+        Ok(spec.filter(InstalledNodeVersion::list(config), &index))
+    }
+
+    /// Resolves LTS aliases to the codename of the line as written in the index
+    fn lts_codename(&self, index: &[OnlineNodeVersion]) -> Option<String> {
+        let mut lines: Vec<(&Version, &String)> = vec![];
+        for version in index {
+            let Some(codename) = &version.lts else {
+                continue;
+            };
+
+            match lines.iter_mut().find(|(_, line)| *line == codename) {
+                Some(line) if line.0 < version.version() => line.0 = version.version(),
+                Some(_) => {},
+                None => lines.push((version.version(), codename)),
+            }
+        }
+        lines.sort_by(|a, b| b.0.cmp(a.0));
+
+        let codename = match self {
+            VersionSpec::LatestLts => lines.first()?.1,
+            VersionSpec::LtsOffset(offset) => lines.get(*offset)?.1,
+            VersionSpec::LtsCodename(codename) => {
+                lines
+                    .iter()
+                    .find(|(_, line)| line.eq_ignore_ascii_case(codename))?
+                    .1
+            },
+            _ => return None,
+        };
+
+        Some(codename.to_owned())
+    }
+}
+
+impl fmt::Display for VersionSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionSpec::Range(range) => write!(f, "{range}"),
+            VersionSpec::Latest => write!(f, "latest"),
+            VersionSpec::LatestLts => write!(f, "lts/*"),
+            VersionSpec::LtsOffset(offset) => write!(f, "lts/-{offset}"),
+            VersionSpec::LtsCodename(codename) => write!(f, "lts/{codename}"),
+            VersionSpec::Alias(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node_semver::Range;
+    use spectral::prelude::*;
+
+    use crate::{
+        node_version::{NodeVersion, OnlineNodeVersion},
+        version_spec::VersionSpec,
+    };
+
+    fn index() -> Vec<OnlineNodeVersion> {
+        serde_json::from_str(
+            r#"[
+                {"version": "v21.6.0", "date": "2024-01-14", "files": [], "lts": false},
+                {"version": "v20.11.0", "date": "2024-01-09", "files": [], "lts": "Iron"},
+                {"version": "v20.10.0", "date": "2023-11-22", "files": [], "lts": "Iron"},
+                {"version": "v20.0.0", "date": "2023-04-18", "files": [], "lts": false},
+                {"version": "v18.19.0", "date": "2023-11-29", "files": [], "lts": "Hydrogen"},
+                {"version": "v16.20.2", "date": "2023-08-08", "files": [], "lts": "Gallium"}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    fn resolve(spec: &str) -> Option<String> {
+        let index = index();
+
+        VersionSpec::parse(spec)
+            .unwrap()
+            .find_online(&index)
+            .map(|version| version.version().to_string())
+    }
+
+    #[test]
+    fn parses_aliases() {
+        assert_that!(VersionSpec::parse("lts/*").unwrap()).is_equal_to(VersionSpec::LatestLts);
+        assert_that!(VersionSpec::parse("lts/-0").unwrap()).is_equal_to(VersionSpec::LatestLts);
+        assert_that!(VersionSpec::parse("lts/-2").unwrap()).is_equal_to(VersionSpec::LtsOffset(2));
+        assert_that!(VersionSpec::parse("lts/Iron").unwrap())
+            .is_equal_to(VersionSpec::LtsCodename("iron".to_string()));
+        assert_that!(VersionSpec::parse("node").unwrap()).is_equal_to(VersionSpec::Latest);
+        assert_that!(VersionSpec::parse(" current\n").unwrap()).is_equal_to(VersionSpec::Latest);
+        assert_that!(VersionSpec::parse("^14.5").unwrap())
+            .is_equal_to(VersionSpec::Range(Range::parse("^14.5").unwrap()));
+        assert_that!(VersionSpec::parse("work").unwrap())
+            .is_equal_to(VersionSpec::Alias("work".to_string()));
+        assert_that!(VersionSpec::parse("lts/-x")).is_err();
+        assert_that!(VersionSpec::parse("not a version")).is_err();
+    }
+
+    #[test]
+    fn resolves_against_index() {
+        assert_that!(resolve("latest")).is_equal_to(Some("21.6.0".to_string()));
+        assert_that!(resolve("lts/*")).is_equal_to(Some("20.11.0".to_string()));
+        assert_that!(resolve("lts/hydrogen")).is_equal_to(Some("18.19.0".to_string()));
+        assert_that!(resolve("lts/-1")).is_equal_to(Some("18.19.0".to_string()));
+        assert_that!(resolve("lts/-2")).is_equal_to(Some("16.20.2".to_string()));
+        assert_that!(resolve("lts/-3")).is_equal_to(None);
+        assert_that!(resolve("lts/argon")).is_equal_to(None);
+        assert_that!(resolve("20")).is_equal_to(Some("20.11.0".to_string()));
+    }
+}
@@ -0,0 +1,129 @@
+mod utils;
+
+mod alias {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[test]
+    fn can_create_and_use_alias() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+
+        let result = cmd.arg("alias").arg("work").arg("12").assert();
+        utils::assert_outputs_contain(&result, "work -> 12 (-> 12.18.3)", "")?;
+
+        let result = utils::nvm(&temp_dir)?.arg("use").arg("work").assert();
+        utils::assert_outputs_contain(&result, "Switched to 12.18.3", "")?;
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("is-installed")
+            .arg("work")
+            .assert();
+        utils::assert_outputs_contain(&result, "is installed (12.18.3)", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn lists_aliases() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::nvm(&temp_dir)?
+            .args(["alias", "work", "14"])
+            .assert()
+            .success();
+        utils::nvm(&temp_dir)?
+            .args(["alias", "old", "10"])
+            .assert()
+            .success();
+
+        let result = cmd.arg("alias").assert();
+
+        let output = String::from_utf8(result.get_output().to_owned().stdout)?;
+        assert_eq!(
+            output.trim(),
+            "old -> 10 (not installed)\nwork -> 14 (-> 14.5.0)"
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn aliases_can_point_to_aliases() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::nvm(&temp_dir)?
+            .args(["alias", "work", "14"])
+            .assert()
+            .success();
+        utils::nvm(&temp_dir)?
+            .args(["alias", "default", "work"])
+            .assert()
+            .success();
+
+        let result = cmd.arg("use").assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 14.5.0", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn refuses_version_as_alias_name() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd.arg("alias").arg("latest").arg("14").assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Error: `latest` can not be used as an alias name since it is already a version",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn can_uninstall_through_alias() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::nvm(&temp_dir)?
+            .args(["alias", "work", "14"])
+            .assert()
+            .success();
+
+        cmd.arg("uninstall").arg("work").assert().success();
+
+        utils::assert_version_installed(&temp_dir, "14.5.0", false)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn can_remove_alias() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::nvm(&temp_dir)?
+            .args(["alias", "work", "14"])
+            .assert()
+            .success();
+
+        let result = cmd.arg("unalias").arg("work").assert();
+        utils::assert_outputs_contain(&result, "Removed alias `work`", "")?;
+
+        let result = utils::nvm(&temp_dir)?.arg("use").arg("work").assert();
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Error: `work` is neither a version nor an alias",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
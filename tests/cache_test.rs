@@ -0,0 +1,106 @@
+mod utils;
+
+mod cache {
+    use std::{
+        fs,
+        time::{Duration, SystemTime},
+    };
+
+    use anyhow::Result;
+
+    use crate::utils;
+
+    fn seed_cache(temp_dir: &std::path::Path) -> Result<()> {
+        let archive_dir = temp_dir
+            .join("cache")
+            .join("downloads")
+            .join("abcdef0123456789");
+        fs::create_dir_all(&archive_dir)?;
+        fs::write(archive_dir.join("node-v14.6.0-linux-x64.tar.gz"), [0; 2048])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn lists_cached_archives() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        seed_cache(&temp_dir)?;
+
+        let result = cmd.arg("cache").arg("list").assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "node-v14.6.0-linux-x64.tar.gz    2.0 KiB abcdef012345 (0 days ago)",
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_cache_size() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        seed_cache(&temp_dir)?;
+
+        let result = cmd.arg("cache").arg("size").assert();
+
+        utils::assert_outputs_contain(&result, "2.0 KiB (1 archives)", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prune_keeps_recently_used_archives() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        seed_cache(&temp_dir)?;
+
+        let result = cmd
+            .arg("cache")
+            .arg("prune")
+            .arg("--older-than")
+            .arg("1d")
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Pruned 0 archives, freeing 0 B", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prune_removes_unused_archives() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        seed_cache(&temp_dir)?;
+        let hash_dir = temp_dir
+            .join("cache")
+            .join("downloads")
+            .join("abcdef0123456789");
+        fs::File::options()
+            .write(true)
+            .open(hash_dir.join("node-v14.6.0-linux-x64.tar.gz"))?
+            .set_modified(SystemTime::now() - Duration::from_secs(60 * 60 * 24 * 2))?;
+
+        let result = cmd
+            .arg("cache")
+            .arg("prune")
+            .arg("--older-than")
+            .arg("1d")
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Pruned 1 archives, freeing 2.0 KiB", "")?;
+        assert!(!hash_dir.exists());
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn clean_removes_everything() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        seed_cache(&temp_dir)?;
+
+        cmd.arg("cache").arg("clean").assert().success();
+
+        assert!(!temp_dir.join("cache").exists());
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
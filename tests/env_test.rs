@@ -0,0 +1,180 @@
+mod utils;
+
+mod env {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[cfg(unix)]
+    fn assert_env_snapshot(shell: &str, use_on_cd: bool) -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        cmd.arg("env").arg("--shell").arg(shell);
+        if use_on_cd {
+            cmd.arg("--use-on-cd");
+        }
+        let result = cmd.assert().success();
+
+        let name = if use_on_cd {
+            format!("env_{shell}_use_on_cd")
+        } else {
+            format!("env_{shell}")
+        };
+        utils::assert_snapshot(&temp_dir, &name, &result.get_output().stdout)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bash() -> Result<()> {
+        assert_env_snapshot("bash", false)?;
+        assert_env_snapshot("bash", true)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn zsh() -> Result<()> {
+        assert_env_snapshot("zsh", false)?;
+        assert_env_snapshot("zsh", true)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fish() -> Result<()> {
+        assert_env_snapshot("fish", false)?;
+        assert_env_snapshot("fish", true)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pwsh() -> Result<()> {
+        assert_env_snapshot("pwsh", false)?;
+        assert_env_snapshot("pwsh", true)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn nu() -> Result<()> {
+        assert_env_snapshot("nu", false)?;
+        assert_env_snapshot("nu", true)
+    }
+
+    #[test]
+    fn fails_without_detectable_shell() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd
+            .env_remove("SHELL")
+            .env_remove("NU_VERSION")
+            .env_remove("PSModulePath")
+            .arg("env")
+            .assert();
+
+        #[cfg(unix)]
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Error: Could not detect which shell you are using, please pass it with `--shell`.",
+        )?;
+        #[cfg(windows)]
+        utils::assert_outputs_contain(&result, "$env:NVM_DIR", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    fn session_shims_dir(output: &[u8]) -> Result<std::path::PathBuf> {
+        let output = String::from_utf8(output.to_vec())?;
+        let line = output
+            .lines()
+            .find_map(|line| line.strip_prefix("export NVM_SHIMS_DIR="))
+            .expect("NVM_SHIMS_DIR was not exported");
+
+        Ok(line.trim_matches('\'').into())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn per_session_only_switches_the_session() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::create_shim(&temp_dir, "12.18.3")?;
+
+        let result = cmd
+            .args(["env", "--shell", "bash", "--per-session"])
+            .assert()
+            .success();
+        let session_dir = session_shims_dir(&result.get_output().stdout)?;
+
+        assert!(session_dir.starts_with(temp_dir.join("sessions")));
+        assert_eq!(
+            std::fs::read_link(&session_dir)?,
+            std::fs::read_link(temp_dir.join("shims"))?
+        );
+
+        utils::nvm(&temp_dir)?
+            .env("NVM_SHIMS_DIR", &session_dir)
+            .args(["use", "14"])
+            .assert()
+            .success();
+
+        assert!(
+            std::fs::read_link(&session_dir)?.starts_with(temp_dir.join("versions").join("14.5.0"))
+        );
+        assert_eq!(
+            utils::get_selected_version(&temp_dir),
+            Some("12.18.3".to_string())
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cleans_up_ended_sessions() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        let sessions_dir = temp_dir.join("sessions");
+        std::fs::create_dir_all(&sessions_dir)?;
+        // Higher than any possible PID
+        let ended_session = sessions_dir.join("999999999_0");
+        std::os::unix::fs::symlink(temp_dir.join("versions").join("12.18.3"), &ended_session)?;
+
+        cmd.args(["env", "--shell", "bash", "--per-session"])
+            .assert()
+            .success();
+
+        assert!(std::fs::symlink_metadata(&ended_session).is_err());
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn per_session_is_not_supported_on_windows() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd
+            .args(["env", "--shell", "pwsh", "--per-session"])
+            .assert();
+
+        utils::assert_outputs_contain(&result, "", "Error: Sessions are not supported on Windows")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn per_session_is_not_supported_in_nu() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd.args(["env", "--shell", "nu", "--per-session"]).assert();
+
+        utils::assert_outputs_contain(&result, "", "Error: Sessions are not supported in Nushell")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
@@ -0,0 +1,125 @@
+mod utils;
+
+#[cfg(unix)]
+mod exec {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[test]
+    fn prepends_version_to_path() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::create_shim(&temp_dir, "14.5.0")?;
+
+        let result = cmd
+            .arg("exec")
+            .arg("12")
+            .arg("--")
+            .arg("sh")
+            .arg("-c")
+            .arg("echo $PATH")
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8(result.get_output().stdout.clone())?;
+        assert!(stdout.starts_with(&format!(
+            "{}:",
+            temp_dir
+                .join("versions")
+                .join("12.18.3")
+                .join("bin")
+                .display()
+        )));
+        assert_eq!(
+            utils::get_selected_version(&temp_dir),
+            Some("14.5.0".to_string())
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn forwards_stdin_and_exit_code() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+
+        cmd.arg("exec")
+            .arg("12")
+            .arg("--")
+            .arg("sh")
+            .arg("-c")
+            .arg("cat; exit 3")
+            .write_stdin("hello")
+            .assert()
+            .code(3)
+            .stdout("hello");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn uses_version_file() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_script(&temp_dir, "12.18.3", "print-version", "echo 12.18.3")?;
+        std::fs::write(temp_dir.join(".nvmrc"), "12\n")?;
+
+        cmd.current_dir(&temp_dir)
+            .arg("exec")
+            .arg("--")
+            .arg("print-version")
+            .assert()
+            .success()
+            .stdout("12.18.3\n");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn fails_if_version_is_missing() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd
+            .arg("exec")
+            .arg("16")
+            .arg("--")
+            .arg("true")
+            .assert()
+            .failure();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "No version matching `>=16.0.0 <17.0.0-0` is installed.",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn installs_missing_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        cmd.arg("exec")
+            .arg("14")
+            .arg("--install-if-missing")
+            .arg("--force")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--")
+            .arg("true")
+            .assert()
+            .success();
+
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+        assert_eq!(utils::get_selected_version(&temp_dir), None);
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
@@ -0,0 +1,70 @@
+mod utils;
+
+mod info {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[test]
+    fn shows_bundled_versions() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        let result = cmd
+            .arg("info")
+            .arg("12")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "12.18.3 (Erbium LTS), released 2020-07-22\nnpm      6.14.6\nV8       7.8.279.23\nlibuv    1.38.0\nzlib     1.2.11\nOpenSSL  1.1.1g\nmodules  72",
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn marks_security_releases() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        let result = cmd
+            .arg("info")
+            .arg("11.14")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "11.14.0, released 2019-04-10\n⚠️ This release contains security fixes",
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn fails_for_unknown_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        let result = cmd
+            .arg("info")
+            .arg("99")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Error: Did not find a version matching `>=99.0.0 <100.0.0-0`!",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
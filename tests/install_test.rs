@@ -1,6 +1,8 @@
 mod utils;
 
 mod install {
+    use std::fs;
+
     use crate::utils;
     use anyhow::Result;
 
@@ -88,4 +90,389 @@ mod install {
 
         temp_dir.close().map_err(anyhow::Error::from)
     }
+
+    #[test]
+    fn downloads_from_mirror() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(vec![("v14.6.0/SHASUMS256.txt", vec![])])?;
+
+        let result = cmd
+            .arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("14")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!("Downloading from {mirror}/v14.6.0/node-v14.6.0-"),
+            "",
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", false)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn refuses_archive_with_mismatched_checksum() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let file_name = utils::archive_name("14.6.0");
+        let mirror = utils::serve_mirror(vec![
+            (
+                "v14.6.0/SHASUMS256.txt",
+                format!("{}  {file_name}\n", "0".repeat(64)).into_bytes(),
+            ),
+            (&format!("v14.6.0/{file_name}"), b"not an archive".to_vec()),
+        ])?;
+
+        let result = cmd
+            .arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("14.6.0")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            &format!("Error: Checksum mismatch for {file_name}!"),
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", false)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn can_install_version_from_mirror() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        let result = cmd
+            .arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("14")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!("Verified checksum of {}", utils::archive_name("14.6.0")),
+            "",
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn prefers_xz_archives() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let xz_name = utils::archive_name("14.6.0").replace(".tar.gz", ".tar.xz");
+        let mirror = utils::serve_mirror(utils::mock_release_with_archives(
+            "14.6.0",
+            vec![
+                (
+                    utils::archive_name("14.6.0"),
+                    utils::mock_archive("14.6.0")?,
+                ),
+                (xz_name.clone(), utils::mock_xz_archive("14.6.0")?),
+            ],
+        ))?;
+
+        let result = cmd
+            .arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("14")
+            .assert();
+
+        utils::assert_outputs_contain(&result, &format!("Verified checksum of {xz_name}"), "")?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn can_force_archive_format() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release_with_archives(
+            "14.6.0",
+            vec![
+                (
+                    utils::archive_name("14.6.0"),
+                    utils::mock_archive("14.6.0")?,
+                ),
+                (
+                    utils::archive_name("14.6.0").replace(".tar.gz", ".tar.xz"),
+                    utils::mock_xz_archive("14.6.0")?,
+                ),
+            ],
+        ))?;
+
+        let result = cmd
+            .arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--archive-format")
+            .arg("gz")
+            .arg("14")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!("Verified checksum of {}", utils::archive_name("14.6.0")),
+            "",
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fails_when_forced_archive_format_is_missing() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        let result = cmd
+            .arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--archive-format")
+            .arg("xz")
+            .arg("14")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            &format!(
+                "Error: Version 14.6.0 does not provide {}",
+                utils::archive_name("14.6.0").replace(".tar.gz", ".tar.xz")
+            ),
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", false)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn can_install_latest_alias() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        cmd.arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("latest")
+            .assert()
+            .success();
+
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn reuses_cached_archive() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        cmd.arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("14.6.0")
+            .assert()
+            .success();
+        fs::remove_dir_all(temp_dir.join("versions").join("14.6.0"))?;
+
+        // Only serve the checksums, so the archive has to come from the cache
+        let shasums = utils::mock_release("14.6.0")?.remove(0);
+        let mirror = utils::serve_mirror(vec![shasums])?;
+        let result = utils::nvm(&temp_dir)?
+            .arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("14.6.0")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!("Using cached {}", utils::archive_name("14.6.0")),
+            "",
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reuses_read_only_cached_archive() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        cmd.arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("14.6.0")
+            .assert()
+            .success();
+        fs::remove_dir_all(temp_dir.join("versions").join("14.6.0"))?;
+
+        // e.g. a cache pre-seeded by CI, which can't be marked as used
+        let hash_dir = fs::read_dir(temp_dir.join("cache").join("downloads"))?
+            .flatten()
+            .find(|entry| entry.path().is_dir())
+            .unwrap()
+            .path();
+        let archive_path = hash_dir.join(utils::archive_name("14.6.0"));
+        fs::set_permissions(&archive_path, fs::Permissions::from_mode(0o444))?;
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("install")
+            .arg("--offline")
+            .arg("14.6.0")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!("Using cached {}", utils::archive_name("14.6.0")),
+            "",
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+        assert!(archive_path.exists());
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn does_not_install_incomplete_archive() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let archive = utils::mock_archive_with_files("14.6.0", &utils::required_files()[..1])?;
+        let mirror = utils::serve_mirror(utils::mock_release_with_archive("14.6.0", archive))?;
+
+        let result = cmd
+            .arg("install")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("14.6.0")
+            .assert();
+
+        utils::assert_outputs_contain(&result, "", "is not preset for \"14.6.0\"")?;
+        assert_eq!(fs::read_dir(temp_dir.join("versions"))?.count(), 0);
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn cleans_up_interrupted_installs() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let staging_dir = temp_dir.join("versions").join(".staging-14.6.0-1234");
+        fs::create_dir_all(staging_dir.join("bin"))?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        cmd.arg("uninstall").arg("12").assert().success();
+
+        assert!(!staging_dir.exists());
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    /// Serves a mock release of 14.6.0 along with `signature_file`, signed by the test key
+    fn serve_signed_release(
+        signature_file: &str,
+        sign: fn(&str) -> Result<Vec<u8>>,
+        signed_text: Option<&str>,
+    ) -> Result<String> {
+        let mut files = utils::mock_release("14.6.0")?;
+        let shasums = String::from_utf8(files[0].1.clone())?;
+        let signature = sign(signed_text.unwrap_or(&shasums))?;
+        files.push((format!("v14.6.0/{signature_file}"), signature));
+
+        utils::serve_mirror(files)
+    }
+
+    fn install_verified(cmd: &mut assert_cmd::Command, mirror: &str) -> assert_cmd::assert::Assert {
+        cmd.arg("install")
+            .arg("--verify-signature")
+            .arg("--keyring")
+            .arg(utils::signature_fixture("keyring.asc"))
+            .arg("--mirror")
+            .arg(mirror)
+            .arg("14.6.0")
+            .assert()
+    }
+
+    #[test]
+    fn verifies_signature() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = serve_signed_release(
+            "SHASUMS256.txt.sig",
+            |text| utils::sign_detached(text.as_bytes()),
+            None,
+        )?;
+
+        let result = install_verified(&mut cmd, &mirror);
+
+        utils::assert_outputs_contain(&result, "Verified signature of SHASUMS256.txt", "")?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn verifies_cleartext_signature_without_detached_one() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = serve_signed_release("SHASUMS256.txt.asc", utils::sign_cleartext, None)?;
+
+        let result = install_verified(&mut cmd, &mirror);
+
+        utils::assert_outputs_contain(&result, "Verified signature of SHASUMS256.txt", "")?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn refuses_bad_signature() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = serve_signed_release(
+            "SHASUMS256.txt.sig",
+            |text| utils::sign_detached(text.as_bytes()),
+            Some("0000  node-v14.6.0-linux-x64.tar.gz\n"),
+        )?;
+
+        let result = install_verified(&mut cmd, &mirror);
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Error: The signature of SHASUMS256.txt was not made by any of the keys in the keyring!",
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", false)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn refuses_missing_signature() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        let result = install_verified(&mut cmd, &mirror);
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Error: Failed to download checksum signature for version: 14.6.0",
+        )?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", false)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
 }
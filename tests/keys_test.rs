@@ -0,0 +1,111 @@
+mod utils;
+
+mod keys {
+    use std::fs;
+
+    use anyhow::Result;
+
+    use crate::utils;
+
+    const TEST_KEY: &str = "550998E03A2C189F4AE4158B084645FDAE6E8F73";
+    const OTHER_KEY: &str = "4EC167A9C2A9999DBCE56AB96F336DA7CC388EFB";
+
+    /// Serves `keys.list` listing `fingerprint`, and `key_file` as the key with that fingerprint
+    fn serve_release_keys(fingerprint: &str, key_file: &str) -> Result<String> {
+        utils::serve_mirror(vec![
+            (
+                "keys.list".to_string(),
+                format!("{fingerprint}\n").into_bytes(),
+            ),
+            (
+                format!("keys/{fingerprint}.asc"),
+                fs::read(utils::signature_fixture(key_file))?,
+            ),
+        ])
+    }
+
+    #[test]
+    fn imports_keys() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd
+            .arg("keys")
+            .arg("import")
+            .arg(utils::signature_fixture("keyring.asc"))
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!("{TEST_KEY} nvm-rust test key <test@example.com>"),
+            "",
+        )?;
+        assert!(temp_dir.join("node-release-keys.asc").exists());
+
+        utils::nvm(&temp_dir)?
+            .arg("keys")
+            .arg("import")
+            .arg(utils::signature_fixture("other-key.asc"))
+            .assert()
+            .success();
+        let result = utils::nvm(&temp_dir)?.arg("keys").arg("list").assert();
+
+        utils::assert_outputs_contain(&result, TEST_KEY, "")?;
+        utils::assert_outputs_contain(&result, OTHER_KEY, "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn updates_keys_from_release_keys_repository() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = serve_release_keys(TEST_KEY, "keyring.asc")?;
+
+        let result = cmd
+            .arg("keys")
+            .arg("update")
+            .arg("--release-keys-url")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Saved 1 release keys to", "")?;
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("keys")
+            .arg("list")
+            .arg("--output")
+            .arg("tsv")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!("fingerprint\tuser_id\n{TEST_KEY}\tnvm-rust test key <test@example.com>"),
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn refuses_release_key_not_matching_fingerprint() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = serve_release_keys(OTHER_KEY, "keyring.asc")?;
+
+        let result = cmd
+            .arg("keys")
+            .arg("update")
+            .arg("--release-keys-url")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            &format!(
+                "Error: The downloaded release key does not match its fingerprint {OTHER_KEY}"
+            ),
+        )?;
+        assert!(!temp_dir.join("node-release-keys.asc").exists());
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
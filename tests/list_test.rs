@@ -0,0 +1,267 @@
+mod utils;
+
+mod list {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[test]
+    fn lists_versions_from_mirror() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+
+        let result = cmd.arg("list").arg("--mirror").arg(&mirror).assert();
+
+        utils::assert_outputs_contain(&result, "〰 14.6.0", "")?;
+        utils::assert_outputs_contain(&result, "⏫ 12.18.2 -> 12.18.3 (Erbium LTS)", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn lists_only_installed_versions() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+
+        let result = cmd.arg("list").arg("--local").assert();
+
+        let output = String::from_utf8(result.get_output().to_owned().stdout)?;
+        assert_eq!(output.trim(), "14.5.0\n12.18.2");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn lists_every_remote_release() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+
+        let result = cmd
+            .arg("list")
+            .arg("--remote")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--release-schedule-url")
+            .arg(format!("{mirror}/schedule.json"))
+            .assert()
+            .success();
+
+        let output = String::from_utf8(result.get_output().to_owned().stdout)?;
+        assert!(output.contains("〰 12.18.3 (Erbium LTS) [EOL]"));
+        assert!(output.contains("✅ 12.18.2 (Erbium LTS) [EOL]"));
+        assert!(output.contains("〰 11.14.0"));
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn lists_only_lts_releases() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        let result = cmd
+            .arg("list")
+            .arg("--remote")
+            .arg("--lts")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--release-schedule-url")
+            .arg(format!("{mirror}/schedule.json"))
+            .assert()
+            .success();
+
+        let output = String::from_utf8(result.get_output().to_owned().stdout)?;
+        assert_eq!(
+            output.trim(),
+            "〰 12.18.3 (Erbium LTS) [EOL]\n〰 12.18.2 (Erbium LTS) [EOL]"
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn omits_eol_without_release_schedule() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        let result = cmd
+            .arg("list")
+            .arg("--remote")
+            .arg("--lts")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert()
+            .success();
+
+        let output = result.get_output();
+        assert_eq!(
+            String::from_utf8(output.stdout.clone())?.trim(),
+            "〰 12.18.3 (Erbium LTS)\n〰 12.18.2 (Erbium LTS)"
+        );
+        assert!(!String::from_utf8(output.stderr.clone())?.contains("release schedule"));
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn does_not_refetch_failed_release_schedule() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+        let schedule_url = format!("{}/schedule.json", utils::UNREACHABLE_MIRROR);
+
+        let result = cmd
+            .arg("list")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--release-schedule-url")
+            .arg(&schedule_url)
+            .assert()
+            .success();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Failed to fetch the release schedule, trying again in 60 minutes.",
+        )?;
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("list")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--release-schedule-url")
+            .arg(&schedule_url)
+            .assert()
+            .success();
+
+        assert!(
+            !String::from_utf8(result.get_output().stderr.clone())?.contains("release schedule")
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn limits_listed_majors() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+
+        let result = cmd
+            .arg("list")
+            .arg("--majors")
+            .arg("1")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert()
+            .success();
+
+        let output = String::from_utf8(result.get_output().to_owned().stdout)?;
+        let versions = output
+            .lines()
+            .filter_map(|line| line.split(' ').nth(1))
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec!["14.6.0", "12.18.2"]);
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn marks_selected_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::create_shim(&temp_dir, "12.18.2")?;
+
+        let result = cmd
+            .arg("list")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--release-schedule-url")
+            .arg(format!("{mirror}/schedule.json"))
+            .assert();
+
+        utils::assert_outputs_contain(&result, "✅ 12.18.2 (Erbium LTS) [selected, EOL]", "")?;
+
+        let result = utils::nvm(&temp_dir)?.arg("list").arg("--local").assert();
+
+        let output = String::from_utf8(result.get_output().to_owned().stdout)?;
+        assert_eq!(output.trim(), "12.18.3\n12.18.2 [selected]");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn uses_cached_index_when_offline() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        cmd.arg("list")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert()
+            .success();
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("list")
+            .arg("--offline")
+            .arg("--mirror")
+            .arg(utils::UNREACHABLE_MIRROR)
+            .assert();
+
+        utils::assert_outputs_contain(&result, "〰 14.6.0", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn fails_offline_without_cached_index() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd.arg("list").arg("--offline").assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Error: There is no cached version index to use in offline mode",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn falls_back_to_stale_index_when_fetching_fails() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        cmd.arg("list")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert()
+            .success();
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("list")
+            .arg("--index-ttl")
+            .arg("0")
+            .arg("--mirror")
+            .arg(utils::UNREACHABLE_MIRROR)
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "〰 14.6.0",
+            "⚠️ Failed to fetch the version index, using the cached one",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
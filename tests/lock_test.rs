@@ -0,0 +1,101 @@
+mod utils;
+
+mod lock {
+    use std::fs::File;
+
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[test]
+    fn waits_for_lock_held_by_other_process() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+
+        let lock_path = temp_dir.join(".lock");
+        std::fs::write(&lock_path, "1234")?;
+        let lock_file = File::options().read(true).write(true).open(&lock_path)?;
+        lock_file.lock()?;
+
+        let result = cmd
+            .arg("use")
+            .arg("12")
+            .arg("--lock-timeout")
+            .arg("1")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "Waiting for lock held by PID 1234...\nError: Timed out after 1s waiting for lock held by PID 1234",
+        )?;
+        assert_eq!(utils::get_selected_version(&temp_dir), None);
+
+        lock_file.unlock()?;
+        cmd.assert().success();
+        assert_eq!(
+            utils::get_selected_version(&temp_dir),
+            Some("12.18.3".to_string())
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn creating_sessions_waits_for_lock() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let lock_file = File::create(temp_dir.join(".lock"))?;
+        lock_file.lock()?;
+
+        let result = cmd
+            .args(["env", "--shell", "bash", "--per-session"])
+            .args(["--lock-timeout", "1"])
+            .assert()
+            .failure();
+
+        utils::assert_outputs_contain(&result, "", "Error: Timed out after 1s")?;
+        assert!(!temp_dir.join("sessions").exists());
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn use_ignores_lock_if_unchanged() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::create_shim(&temp_dir, "12.18.3")?;
+
+        let lock_file = File::create(temp_dir.join(".lock"))?;
+        lock_file.lock()?;
+
+        let result = cmd
+            .arg("use")
+            .arg("12")
+            .arg("--silent-if-unchanged")
+            .arg("--lock-timeout")
+            .arg("1")
+            .assert()
+            .success();
+
+        assert_eq!(String::from_utf8(result.get_output().stderr.clone())?, "");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn read_only_commands_ignore_lock() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+
+        let lock_file = File::create(temp_dir.join(".lock"))?;
+        lock_file.lock()?;
+
+        let result = cmd.arg("list").arg("--local").assert();
+
+        utils::assert_outputs_contain(&result, "12.18.3", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
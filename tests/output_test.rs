@@ -0,0 +1,346 @@
+mod utils;
+
+mod output {
+    use anyhow::Result;
+    use serde_json::{json, Value};
+
+    use crate::utils;
+
+    fn stdout_json(result: &assert_cmd::assert::Assert) -> Result<Value> {
+        Ok(serde_json::from_slice(&result.get_output().stdout)?)
+    }
+
+    #[test]
+    fn lists_installed_versions_as_json() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::create_shim(&temp_dir, "14.5.0")?;
+
+        let result = cmd
+            .arg("list")
+            .arg("--local")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .success();
+
+        let entry = |version: &str, selected: bool| {
+            json!({
+                "version": version,
+                "installed": true,
+                "selected": selected,
+                "lts": null,
+                "security": null,
+                "latest_in_major": null,
+                "status": null,
+                "eol": null,
+            })
+        };
+        assert_eq!(
+            stdout_json(&result)?,
+            json!([entry("14.5.0", true), entry("12.18.3", false)])
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn lists_versions_as_tsv() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+
+        let result = cmd
+            .arg("list")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--release-schedule-url")
+            .arg(format!("{mirror}/schedule.json"))
+            .arg("--output")
+            .arg("tsv")
+            .assert()
+            .success();
+
+        let stdout = String::from_utf8(result.get_output().stdout.clone())?;
+        let mut lines = stdout.lines();
+        assert_eq!(
+            lines.next(),
+            Some("version\tinstalled\tselected\tlts\tsecurity\tlatest_in_major\tstatus\teol")
+        );
+        assert!(lines
+            .any(|line| line == "12.18.2\ttrue\tfalse\tErbium\tfalse\t12.18.3\toutdated\ttrue"));
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_is_installed_as_json() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+
+        let result = cmd
+            .arg("is-installed")
+            .arg("12")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .success();
+        assert_eq!(
+            stdout_json(&result)?,
+            json!({ "requested": ">=12.0.0 <13.0.0-0", "installed": true, "version": "12.18.3" })
+        );
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("is-installed")
+            .arg("14")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .code(1);
+        assert_eq!(
+            stdout_json(&result)?,
+            json!({ "requested": ">=14.0.0 <15.0.0-0", "installed": false, "version": null })
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_install_as_json() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        let result = cmd
+            .arg("install")
+            .arg("14")
+            .arg("--mirror")
+            .arg(&mirror)
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .success();
+
+        // Progress messages go to stderr, so stdout only contains the result
+        assert_eq!(
+            stdout_json(&result)?,
+            json!({
+                "version": "14.6.0",
+                "path": temp_dir.join("versions").join("14.6.0"),
+                "already_installed": false,
+                "switched": false,
+            })
+        );
+        utils::assert_outputs_contain(&result, "", "Extracted to")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_use_as_json() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        std::fs::write(temp_dir.join(".nvmrc"), "12\n")?;
+
+        let result = cmd
+            .current_dir(&temp_dir)
+            .arg("use")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .success();
+
+        assert_eq!(
+            stdout_json(&result)?,
+            json!({ "version": "12.18.3", "version_file": temp_dir.join(".nvmrc"), "switched": true })
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_use_as_json_when_keeping_version() -> Result<()> {
+        let (temp_dir, _) = utils::setup_integration_test()?;
+        let project_dir = temp_dir.join("project");
+        std::fs::create_dir(&project_dir)?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::create_shim(&temp_dir, "12.18.3")?;
+        std::fs::write(
+            project_dir.join("package.json"),
+            r#"{ "devEngines": { "runtime": { "name": "node", "version": "^20", "onFail": "warn" } } }"#,
+        )?;
+
+        let use_json = |dir: &std::path::Path, args: &[&str]| -> Result<Value> {
+            let result = utils::nvm(&temp_dir)?
+                .current_dir(dir)
+                .arg("use")
+                .args(args)
+                .arg("--output")
+                .arg("json")
+                .assert()
+                .success();
+
+            stdout_json(&result)
+        };
+
+        // Already selected
+        assert_eq!(
+            use_json(&temp_dir, &["12", "--silent-if-unchanged"])?,
+            json!({ "version": "12.18.3", "version_file": null, "switched": false })
+        );
+        // No version file
+        assert_eq!(
+            use_json(&temp_dir, &["--silent-if-unchanged"])?,
+            json!({ "version": "12.18.3", "version_file": null, "switched": false })
+        );
+        // `onFail: warn` without a matching version
+        assert_eq!(
+            use_json(&project_dir, &[])?,
+            json!({
+                "version": "12.18.3",
+                "version_file": project_dir.join("package.json"),
+                "switched": false
+            })
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_version_file_diagnostics_to_stderr() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        std::fs::create_dir(temp_dir.join(".git"))?;
+        std::fs::write(temp_dir.join("package.json"), "{ not json")?;
+        std::fs::write(temp_dir.join(".nvmrc"), "12\n")?;
+
+        let result = cmd
+            .current_dir(&temp_dir)
+            .arg("is-installed")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .success();
+
+        assert_eq!(
+            stdout_json(&result)?,
+            json!({ "requested": ">=12.0.0 <13.0.0-0", "installed": true, "version": "12.18.3" })
+        );
+        utils::assert_outputs_contain(&result, "", "Failed to parse")?;
+
+        let result = utils::nvm(&temp_dir)?
+            .current_dir(&temp_dir)
+            .arg("version-file")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .success();
+
+        let files = stdout_json(&result)?;
+        assert_eq!(files[0]["path"], json!(temp_dir.join("package.json")));
+        assert_eq!(files[0]["status"], json!("failed"));
+        assert_eq!(
+            files[1],
+            json!({
+                "path": temp_dir.join(".nvmrc"),
+                "source": ".nvmrc",
+                "status": "used",
+                "version": ">=12.0.0 <13.0.0-0",
+                "reason": null,
+            })
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_cache_as_tsv() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let archive_dir = temp_dir.join("cache").join("downloads").join("abcdef");
+        std::fs::create_dir_all(&archive_dir)?;
+        std::fs::write(archive_dir.join("node-v14.6.0-linux-x64.tar.gz"), [0; 2048])?;
+
+        cmd.arg("cache")
+            .arg("list")
+            .arg("--output")
+            .arg("tsv")
+            .assert()
+            .success()
+            .stdout(
+                "file_name\tsize\thash\tage_days\nnode-v14.6.0-linux-x64.tar.gz\t2048\tabcdef\t0\n",
+            );
+        utils::nvm(&temp_dir)?
+            .arg("cache")
+            .arg("size")
+            .arg("--output")
+            .arg("tsv")
+            .assert()
+            .success()
+            .stdout("size\tarchives\n2048\t1\n");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_unalias_and_parse_version_as_json() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        cmd.arg("alias").arg("work").arg("18").assert().success();
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("unalias")
+            .arg("work")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .success();
+        assert_eq!(
+            stdout_json(&result)?,
+            json!({ "name": "work", "target": "18" })
+        );
+
+        let result = utils::nvm(&temp_dir)?
+            .arg("parse-version")
+            .arg("^14.5")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .success();
+        assert_eq!(
+            stdout_json(&result)?,
+            json!({
+                "input": "^14.5",
+                "version_file": null,
+                "parsed": ">=14.5.0 <15.0.0-0",
+                "version": null,
+            })
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn fails_for_commands_without_structured_output() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd
+            .arg("env")
+            .arg("--shell")
+            .arg("bash")
+            .arg("--output")
+            .arg("json")
+            .assert()
+            .failure();
+
+        utils::assert_outputs_contain(&result, "", "This command only supports `--output text`")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
@@ -0,0 +1,106 @@
+mod utils;
+
+#[cfg(unix)]
+mod run {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    const MOCK_NODE: &str = "echo \"$(basename \"$(dirname \"$(dirname \"$0\")\")\") $*\"";
+
+    #[test]
+    fn runs_node_of_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::install_mock_script(&temp_dir, "12.18.3", "node", MOCK_NODE)?;
+        utils::create_shim(&temp_dir, "14.5.0")?;
+
+        cmd.arg("run")
+            .arg("12")
+            .arg("script.js")
+            .arg("--flag")
+            .assert()
+            .success()
+            .stdout("12.18.3 script.js --flag\n")
+            .stderr("");
+        assert_eq!(
+            utils::get_selected_version(&temp_dir),
+            Some("14.5.0".to_string())
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn uses_version_file_when_first_arg_is_not_a_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::install_mock_script(&temp_dir, "12.18.3", "node", MOCK_NODE)?;
+        std::fs::write(temp_dir.join(".nvmrc"), "12\n")?;
+
+        let result = cmd
+            .current_dir(&temp_dir)
+            .arg("run")
+            .arg("--verbose")
+            .arg("script.js")
+            .assert()
+            .success()
+            .stdout("12.18.3 script.js\n");
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            &format!(
+                "Running node 12.18.3 (>=12.0.0 <13.0.0-0 from {})",
+                temp_dir.join(".nvmrc").display()
+            ),
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn runs_scripts_named_like_ranges() -> Result<()> {
+        let (temp_dir, _) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::install_mock_script(&temp_dir, "12.18.3", "node", MOCK_NODE)?;
+        std::fs::write(temp_dir.join(".nvmrc"), "12\n")?;
+        std::fs::write(temp_dir.join("14"), "")?;
+
+        // `x` is a valid range, but doesn't look like a version
+        for script in ["x", "14"] {
+            utils::nvm(&temp_dir)?
+                .current_dir(&temp_dir)
+                .arg("run")
+                .arg(script)
+                .assert()
+                .success()
+                .stdout(format!("12.18.3 {script}\n"));
+        }
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn propagates_exit_code() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_script(&temp_dir, "12.18.3", "node", "exit 4")?;
+
+        cmd.arg("run")
+            .arg("12")
+            .arg("-e")
+            .arg("process.exit(4)")
+            .assert()
+            .code(4);
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
@@ -45,4 +45,303 @@ mod switch {
 
         temp_dir.close().map_err(anyhow::Error::from)
     }
+
+    #[test]
+    fn can_switch_to_lts_alias() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+
+        let result = cmd
+            .arg("use")
+            .arg("lts/*")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 12.18.3", "")?;
+        assert_eq!(
+            utils::get_selected_version(&temp_dir),
+            Some("12.18.3".to_string())
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn can_switch_to_lts_alias_from_nvmrc() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_index()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        std::fs::write(temp_dir.join(".nvmrc"), "lts/erbium\n")?;
+
+        let result = cmd
+            .current_dir(&temp_dir)
+            .arg("use")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 12.18.2", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn silent_if_unchanged_when_selected_version_matches() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::create_shim(&temp_dir, "12.18.2")?;
+
+        let result = cmd
+            .arg("use")
+            .arg("12")
+            .arg("--silent-if-unchanged")
+            .assert()
+            .success();
+
+        assert_eq!(String::from_utf8(result.get_output().stdout.clone())?, "");
+        assert_eq!(
+            utils::get_selected_version(&temp_dir),
+            Some("12.18.2".to_string())
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn silent_if_unchanged_still_switches() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::create_shim(&temp_dir, "12.18.3")?;
+
+        let result = cmd
+            .arg("use")
+            .arg("14")
+            .arg("--silent-if-unchanged")
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 14.5.0", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn silent_if_unchanged_without_version_file() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let result = cmd
+            .current_dir(&temp_dir)
+            .arg("use")
+            .arg("--silent-if-unchanged")
+            .assert()
+            .success();
+
+        assert_eq!(String::from_utf8(result.get_output().stdout.clone())?, "");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn silent_if_unchanged_ignores_default_alias_without_version_file() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::create_shim(&temp_dir, "12.18.3")?;
+        cmd.arg("alias").arg("default").arg("14").assert().success();
+
+        let result = utils::nvm(&temp_dir)?
+            .current_dir(&temp_dir)
+            .arg("use")
+            .arg("--silent-if-unchanged")
+            .assert()
+            .success();
+
+        assert_eq!(String::from_utf8(result.get_output().stdout.clone())?, "");
+        assert_eq!(
+            utils::get_selected_version(&temp_dir),
+            Some("12.18.3".to_string())
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn can_install_missing_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        let result = cmd
+            .arg("use")
+            .arg("14")
+            .arg("--install-if-missing")
+            .arg("--force")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 14.6.0", "")?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn finds_version_file_in_parent_directory() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        let package_dir = temp_dir.join("packages").join("foo");
+        std::fs::create_dir_all(&package_dir)?;
+        std::fs::write(temp_dir.join(".nvmrc"), "12\n")?;
+        // Packages without engines don't stop the search
+        std::fs::write(package_dir.join("package.json"), r#"{ "name": "foo" }"#)?;
+
+        let result = cmd.current_dir(&package_dir).arg("use").assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!("from {}", temp_dir.join(".nvmrc").display()),
+            "",
+        )?;
+        utils::assert_outputs_contain(&result, "Switched to 12.18.3", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn stops_searching_at_git_root() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        let package_dir = temp_dir.join("repo").join("packages").join("foo");
+        std::fs::create_dir_all(&package_dir)?;
+        std::fs::create_dir(temp_dir.join("repo").join(".git"))?;
+        std::fs::write(temp_dir.join(".nvmrc"), "12\n")?;
+
+        let result = cmd.current_dir(&package_dir).arg("use").assert();
+
+        utils::assert_outputs_contain(&result, "", "we did not find any version files")?;
+
+        let result = utils::nvm(&temp_dir)?
+            .current_dir(&package_dir)
+            .arg("use")
+            .arg("--version-file-boundary")
+            .arg("root")
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 12.18.3", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn falls_through_package_json_without_engines() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        std::fs::create_dir(temp_dir.join(".git"))?;
+        std::fs::write(temp_dir.join("package.json"), r#"{ "name": "foo" }"#)?;
+        std::fs::write(temp_dir.join(".nvmrc"), "12\n")?;
+
+        let result = cmd.current_dir(&temp_dir).arg("use").assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 12.18.3", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prefers_volta_pin_over_engines() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.2")?;
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        std::fs::write(
+            temp_dir.join("package.json"),
+            r#"{ "engines": { "node": ">=12" }, "volta": { "node": "12.18.2" } }"#,
+        )?;
+
+        let result = cmd.current_dir(&temp_dir).arg("use").assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 12.18.2", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn warns_for_missing_dev_engines_runtime() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::create_shim(&temp_dir, "12.18.3")?;
+        std::fs::write(
+            temp_dir.join("package.json"),
+            r#"{ "devEngines": { "runtime": { "name": "node", "version": "^20", "onFail": "warn" } } }"#,
+        )?;
+
+        let result = cmd.current_dir(&temp_dir).arg("use").assert().success();
+
+        utils::assert_outputs_contain(
+            &result,
+            "",
+            "No installed version matches `>=20.0.0 <21.0.0-0`, keeping the current version",
+        )?;
+        assert_eq!(
+            utils::get_selected_version(&temp_dir),
+            Some("12.18.3".to_string())
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn downloads_missing_dev_engines_runtime() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+        let mirror = utils::serve_mirror(utils::mock_release("14.6.0")?)?;
+
+        std::fs::write(
+            temp_dir.join("package.json"),
+            r#"{ "devEngines": { "runtime": { "name": "node", "version": "14.6.0", "onFail": "download" } } }"#,
+        )?;
+
+        let result = cmd
+            .current_dir(&temp_dir)
+            .arg("use")
+            .arg("--mirror")
+            .arg(&mirror)
+            .assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 14.6.0", "")?;
+        utils::assert_version_installed(&temp_dir, "14.6.0", true)?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn falls_back_to_installed_tool_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        std::fs::write(
+            temp_dir.join(".tool-versions"),
+            "ruby 3.3.0\nnodejs\t14.5.0  12.18.3 # fallback\n",
+        )?;
+
+        let result = cmd.current_dir(&temp_dir).arg("use").assert();
+
+        utils::assert_outputs_contain(&result, "Switched to 12.18.3", "")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
 }
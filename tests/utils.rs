@@ -2,13 +2,29 @@
 use std::os::unix::fs::symlink;
 #[cfg(windows)]
 use std::os::windows::fs::symlink_dir;
-use std::{fs, path::Path};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufReader, Cursor, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    thread,
+};
 
 use anyhow::Result;
 use assert_cmd::{assert::Assert, Command};
 use assert_fs::{prelude::*, TempDir};
+use pgp::{
+    crypto::hash::HashAlgorithm,
+    packet::{SignatureConfigBuilder, SignatureType, SignatureVersion, Subpacket, SubpacketData},
+    ser::Serialize,
+    types::KeyTrait,
+    Deserializable, SignedSecretKey, StandaloneSignature,
+};
 use predicates::prelude::*;
+use sha2::{Digest, Sha256};
 
+#[allow(dead_code)]
 #[cfg(unix)]
 pub fn required_files<'a>() -> [&'a str; 3] {
     ["node", "npm", "npx"]
@@ -26,15 +42,233 @@ fn integration_dir() -> TempDir {
     dir
 }
 
+/// A mirror URL nothing is listening on
+#[allow(dead_code)]
+pub const UNREACHABLE_MIRROR: &str = "http://127.0.0.1:9";
+
 pub fn setup_integration_test() -> Result<(TempDir, Command)> {
     let temp_dir = integration_dir();
-
-    let mut cmd = Command::cargo_bin("nvm").expect("Could not create Command");
-    cmd.args(["--install-dir", &temp_dir.to_string_lossy()]);
+    let cmd = nvm(&temp_dir)?;
 
     Ok((temp_dir, cmd))
 }
 
+/// Creates another command using the same installation dir
+pub fn nvm(temp_dir: &Path) -> Result<Command> {
+    let mut cmd = Command::cargo_bin("nvm")?;
+    cmd.args(["--install-dir", &temp_dir.to_string_lossy()])
+        .env("NVM_RELEASE_KEYS_URL", UNREACHABLE_MIRROR);
+
+    Ok(cmd)
+}
+
+/// Name of the archive nvm downloads for a version on this platform
+#[allow(dead_code)]
+pub fn archive_name(version_str: &str) -> String {
+    #[cfg(target_os = "windows")]
+    let (platform, ext) = ("win", ".zip");
+    #[cfg(target_os = "macos")]
+    let (platform, ext) = ("darwin", ".tar.gz");
+    #[cfg(target_os = "linux")]
+    let (platform, ext) = ("linux", ".tar.gz");
+
+    // The test data doesn't list any arm64 files for macOS, so it falls back to x64 there
+    #[cfg(any(target_arch = "x86_64", target_os = "macos"))]
+    let arch = "x64";
+    #[cfg(all(target_arch = "aarch64", not(target_os = "macos")))]
+    let arch = "arm64";
+
+    format!("node-v{version_str}-{platform}-{arch}{ext}")
+}
+
+/// Creates an archive in the same layout as the ones on nodejs.org, containing mock binaries
+#[allow(dead_code)]
+pub fn mock_archive(version_str: &str) -> Result<Vec<u8>> {
+    mock_archive_with_files(version_str, &required_files())
+}
+
+#[allow(dead_code)]
+#[cfg(unix)]
+pub fn mock_archive_with_files(version_str: &str, files: &[&str]) -> Result<Vec<u8>> {
+    use flate2::{write::GzEncoder, Compression};
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(&mock_tar(version_str, files)?)?;
+
+    Ok(encoder.finish()?)
+}
+
+/// Same as `mock_archive`, but compressed as a `.tar.xz` archive
+#[allow(dead_code)]
+#[cfg(unix)]
+pub fn mock_xz_archive(version_str: &str) -> Result<Vec<u8>> {
+    let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 1);
+    encoder.write_all(&mock_tar(version_str, &required_files())?)?;
+
+    Ok(encoder.finish()?)
+}
+
+#[cfg(unix)]
+fn mock_tar(version_str: &str, files: &[&str]) -> Result<Vec<u8>> {
+    let root = archive_name(version_str).replace(".tar.gz", "");
+    let mut builder = tar::Builder::new(Vec::new());
+
+    for dir in [format!("{root}/"), format!("{root}/bin/")] {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_size(0);
+        header.set_mode(0o755);
+        header.set_cksum();
+
+        builder.append_data(&mut header, dir, std::io::empty())?;
+    }
+
+    for file_name in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(version_str.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+
+        builder.append_data(
+            &mut header,
+            format!("{root}/bin/{file_name}"),
+            version_str.as_bytes(),
+        )?;
+    }
+
+    Ok(builder.into_inner()?)
+}
+
+#[allow(dead_code)]
+#[cfg(windows)]
+pub fn mock_archive_with_files(version_str: &str, files: &[&str]) -> Result<Vec<u8>> {
+    use std::io::Cursor;
+
+    let root = archive_name(version_str).replace(".zip", "");
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+    for file_name in files {
+        writer.start_file(
+            format!("{root}/{file_name}"),
+            zip::write::FileOptions::default(),
+        )?;
+        writer.write_all(version_str.as_bytes())?;
+    }
+
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Files to serve from a mirror so `version_str` can be installed
+#[allow(dead_code)]
+pub fn mock_release(version_str: &str) -> Result<Vec<(String, Vec<u8>)>> {
+    Ok(mock_release_with_archive(
+        version_str,
+        mock_archive(version_str)?,
+    ))
+}
+
+#[allow(dead_code)]
+pub fn mock_release_with_archive(version_str: &str, archive: Vec<u8>) -> Vec<(String, Vec<u8>)> {
+    mock_release_with_archives(version_str, vec![(archive_name(version_str), archive)])
+}
+
+/// Files to serve from a mirror for a version providing several kinds of archives
+#[allow(dead_code)]
+pub fn mock_release_with_archives(
+    version_str: &str,
+    archives: Vec<(String, Vec<u8>)>,
+) -> Vec<(String, Vec<u8>)> {
+    let shasums: String = archives
+        .iter()
+        .map(|(file_name, archive)| {
+            let hash: String = Sha256::digest(archive)
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect();
+
+            format!("{hash}  {file_name}\n")
+        })
+        .collect();
+
+    let mut files = vec![(
+        format!("v{version_str}/SHASUMS256.txt"),
+        shasums.into_bytes(),
+    )];
+    files.extend(
+        archives
+            .into_iter()
+            .map(|(file_name, archive)| (format!("v{version_str}/{file_name}"), archive)),
+    );
+
+    files
+}
+
+/// Starts a mirror only serving the version index
+#[allow(dead_code)]
+pub fn serve_index() -> Result<String> {
+    serve_mirror(Vec::<(String, Vec<u8>)>::new())
+}
+
+/// Starts a local HTTP server standing in for the nodejs.org distribution.
+///
+/// Serves `index.json` from `test-data/node-versions.json` and `schedule.json` from
+/// `test-data/release-schedule.json` in addition to the passed files, and returns the URL to pass
+/// as `--mirror`.
+#[allow(dead_code)]
+pub fn serve_mirror<P: AsRef<str>>(files: Vec<(P, Vec<u8>)>) -> Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+
+    let mut files: HashMap<String, Vec<u8>> = files
+        .into_iter()
+        .map(|(path, body)| (format!("/{}", path.as_ref()), body))
+        .collect();
+    files.insert(
+        "/index.json".to_string(),
+        fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/node-versions.json"))?,
+    );
+    files.insert(
+        "/schedule.json".to_string(),
+        fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/release-schedule.json"))?,
+    );
+
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut request_line = String::new();
+            let mut reader = BufReader::new(&stream);
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+
+            // Drain the headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok() && line.trim() != "" {
+                line.clear();
+            }
+
+            let path = request_line.split(' ').nth(1).unwrap_or_default();
+            let response = match files.get(path) {
+                Some(body) => [
+                    format!(
+                        "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: {}\r\n\r\n",
+                        body.len()
+                    )
+                    .into_bytes(),
+                    body.clone(),
+                ]
+                .concat(),
+                None => b"HTTP/1.1 404 Not Found\r\nConnection: close\r\nContent-Length: 0\r\n\r\n"
+                    .to_vec(),
+            };
+
+            let _ = stream.write_all(&response);
+        }
+    });
+
+    Ok(url)
+}
+
+#[allow(dead_code)]
 pub fn install_mock_version(path: &Path, version_str: &str) -> Result<()> {
     let mut to_dir = path.join("versions");
 
@@ -57,6 +291,23 @@ pub fn install_mock_version(path: &Path, version_str: &str) -> Result<()> {
     Ok(())
 }
 
+/// Adds an executable shell script to an installed mock version, e.g. to replace `node`
+#[allow(dead_code)]
+#[cfg(unix)]
+pub fn install_mock_script(path: &Path, version_str: &str, name: &str, script: &str) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script_path = path
+        .join("versions")
+        .join(version_str)
+        .join("bin")
+        .join(name);
+    fs::write(&script_path, format!("#!/bin/sh\n{script}\n"))?;
+    fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755))?;
+
+    Ok(())
+}
+
 #[allow(dead_code)]
 #[cfg(windows)]
 pub fn create_shim(temp_dir: &Path, version_str: &str) -> Result<()> {
@@ -67,6 +318,7 @@ pub fn create_shim(temp_dir: &Path, version_str: &str) -> Result<()> {
     .map_err(anyhow::Error::from)
 }
 
+#[allow(dead_code)]
 #[cfg(unix)]
 pub fn create_shim(temp_dir: &Path, version_str: &str) -> Result<()> {
     let mut shims_path = temp_dir.join("versions").join(version_str);
@@ -157,3 +409,84 @@ pub fn get_selected_version(temp_dir: &TempDir) -> Option<String> {
         Err(_) => None,
     }
 }
+
+/// Compares `output` to `tests/snapshots/<name>.txt`, with the installation dir replaced by
+/// `<NVM_DIR>`. Set `UPDATE_SNAPSHOTS=1` to write the current output to the snapshot instead.
+#[allow(dead_code)]
+pub fn assert_snapshot(temp_dir: &Path, name: &str, output: &[u8]) -> Result<()> {
+    let output =
+        String::from_utf8(output.to_vec())?.replace(&*temp_dir.to_string_lossy(), "<NVM_DIR>");
+    let snapshot_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{name}.txt"));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(snapshot_path.parent().unwrap())?;
+        fs::write(&snapshot_path, &output)?;
+    }
+
+    let snapshot = fs::read_to_string(&snapshot_path)
+        .map_err(|err| anyhow::anyhow!("Could not read {snapshot_path:?}: {err}"))?;
+    assert_eq!(
+        output, snapshot,
+        "Output does not match {snapshot_path:?}, run with UPDATE_SNAPSHOTS=1 to update it"
+    );
+
+    Ok(())
+}
+
+/// Path of a file in `test-data/signature`, e.g. the keyring containing the test key
+#[allow(dead_code)]
+pub fn signature_fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("test-data/signature")
+        .join(name)
+}
+
+#[allow(dead_code)]
+fn sign(typ: SignatureType, data: &[u8]) -> Result<StandaloneSignature> {
+    let (key, _) = SignedSecretKey::from_armor_single(Cursor::new(fs::read(signature_fixture(
+        "secret-key.asc",
+    ))?))?;
+    let now = chrono::Utc::now();
+
+    let signature = SignatureConfigBuilder::default()
+        .version(SignatureVersion::V4)
+        .typ(typ)
+        .pub_alg(key.algorithm())
+        .hash_alg(HashAlgorithm::SHA2_256)
+        .issuer(Some(key.key_id()))
+        .created(Some(now))
+        .unhashed_subpackets(vec![])
+        .hashed_subpackets(vec![
+            Subpacket::regular(SubpacketData::SignatureCreationTime(now)),
+            Subpacket::regular(SubpacketData::Issuer(key.key_id())),
+        ])
+        .build()?
+        .sign(&key, String::new, data)?;
+
+    Ok(StandaloneSignature::new(signature))
+}
+
+/// Signs `data` with the test key, like `gpg --detach-sign`
+#[allow(dead_code)]
+pub fn sign_detached(data: &[u8]) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    sign(SignatureType::Binary, data)?.to_writer(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+/// Signs `text` with the test key, like `gpg --clearsign`
+#[allow(dead_code)]
+pub fn sign_cleartext(text: &str) -> Result<Vec<u8>> {
+    let canonical = text.lines().collect::<Vec<_>>().join("\r\n");
+    let signature = sign(SignatureType::Text, canonical.as_bytes())?.to_armored_string(None)?;
+
+    Ok(format!(
+        "-----BEGIN PGP SIGNED MESSAGE-----\nHash: SHA256\n\n{}\n{signature}",
+        text.trim_end()
+    )
+    .into_bytes())
+}
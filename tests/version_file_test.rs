@@ -0,0 +1,113 @@
+mod utils;
+
+mod version_file {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[test]
+    fn explains_used_and_skipped_files() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        let package_dir = temp_dir.join("packages").join("foo");
+        std::fs::create_dir_all(&package_dir)?;
+        std::fs::create_dir(temp_dir.join(".git"))?;
+        std::fs::write(package_dir.join("package.json"), r#"{ "name": "foo" }"#)?;
+        std::fs::write(temp_dir.join(".nvmrc"), "14\n")?;
+        std::fs::write(temp_dir.join(".tool-versions"), "nodejs 12.18.3\n")?;
+
+        let result = cmd.current_dir(&package_dir).arg("version-file").assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!(
+                "⏭️ {}: skipped, it has no `volta.node`",
+                package_dir.join("package.json").display()
+            ),
+            "",
+        )?;
+        utils::assert_outputs_contain(
+            &result,
+            &format!(
+                "✅ {}: using >=14.0.0 <15.0.0-0",
+                temp_dir.join(".nvmrc").display()
+            ),
+            "",
+        )?;
+        utils::assert_outputs_contain(
+            &result,
+            &format!(
+                "⏭️ {}: ignored 12.18.3",
+                temp_dir.join(".tool-versions").display()
+            ),
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn uses_configured_order() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        std::fs::create_dir(temp_dir.join(".git"))?;
+        std::fs::write(temp_dir.join(".nvmrc"), "14\n")?;
+        std::fs::write(temp_dir.join(".tool-versions"), "nodejs 12.18.3\n")?;
+
+        let result = cmd
+            .current_dir(&temp_dir)
+            .arg("version-file")
+            .arg("--version-file-order")
+            .arg("tool-versions,nvmrc")
+            .assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            &format!(
+                "✅ {}: using 12.18.3",
+                temp_dir.join(".tool-versions").display()
+            ),
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn reports_when_nothing_is_found() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        std::fs::create_dir(temp_dir.join(".git"))?;
+
+        let result = cmd.current_dir(&temp_dir).arg("version-file").assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "No version file specifying a node version was found",
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn reports_unsupported_tool_versions() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        std::fs::create_dir(temp_dir.join(".git"))?;
+        std::fs::write(
+            temp_dir.join(".tool-versions"),
+            "nodejs ref:v20.11.0 system\n",
+        )?;
+
+        let result = cmd.current_dir(&temp_dir).arg("version-file").assert();
+
+        utils::assert_outputs_contain(
+            &result,
+            "failed to parse: `nodejs` only lists `ref:v20.11.0`, `system`, nvm-rust only supports released versions",
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
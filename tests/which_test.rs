@@ -0,0 +1,140 @@
+mod utils;
+
+mod current {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[test]
+    fn prints_selected_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+        utils::create_shim(&temp_dir, "12.18.3")?;
+
+        cmd.arg("current").assert().success().stdout("12.18.3\n");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn does_not_select_versions_with_matching_prefix() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "12.18.30")?;
+        utils::create_shim(&temp_dir, "12.18.30")?;
+
+        cmd.arg("current").assert().success().stdout("12.18.30\n");
+        utils::nvm(&temp_dir)?
+            .arg("list")
+            .arg("--local")
+            .assert()
+            .success()
+            .stdout("12.18.30 [selected]\n12.18.3\n");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn prints_none_without_selected_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+
+        cmd.arg("current").assert().success().stdout("none\n");
+        utils::nvm(&temp_dir)?
+            .arg("current")
+            .arg("--json")
+            .assert()
+            .success()
+            .stdout("{\"version\":null,\"path\":null}\n");
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}
+
+mod which {
+    use anyhow::Result;
+
+    use crate::utils;
+
+    #[cfg(unix)]
+    fn bin_path(temp_dir: &std::path::Path, version: &str) -> std::path::PathBuf {
+        temp_dir.join("versions").join(version).join("bin")
+    }
+
+    #[cfg(windows)]
+    fn bin_path(temp_dir: &std::path::Path, version: &str) -> std::path::PathBuf {
+        temp_dir.join("versions").join(version)
+    }
+
+    #[test]
+    fn prints_path_of_matching_version() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "12.18.3")?;
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+
+        let result = cmd.arg("which").arg("12").assert().success();
+
+        utils::assert_outputs_contain(
+            &result,
+            &bin_path(&temp_dir, "12.18.3")
+                .join(format!("node{}", std::env::consts::EXE_SUFFIX))
+                .to_string_lossy(),
+            "",
+        )?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn prints_json_for_other_binaries() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+
+        let result = cmd
+            .arg("which")
+            .arg("14")
+            .arg("--bin")
+            .arg("npx")
+            .arg("--json")
+            .assert()
+            .success();
+
+        let output: serde_json::Value = serde_json::from_slice(&result.get_output().stdout)?;
+        assert_eq!(
+            output,
+            serde_json::json!({
+                "version": "14.5.0",
+                "bin": "npx",
+                "path": bin_path(&temp_dir, "14.5.0").join("npx"),
+            })
+        );
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+
+    #[test]
+    fn fails_for_missing_binary() -> Result<()> {
+        let (temp_dir, mut cmd) = utils::setup_integration_test()?;
+
+        utils::install_mock_version(&temp_dir, "14.5.0")?;
+
+        let result = cmd
+            .arg("which")
+            .arg("14")
+            .arg("--bin")
+            .arg("corepack")
+            .assert()
+            .failure();
+
+        utils::assert_outputs_contain(&result, "", "14.5.0 does not include \"corepack")?;
+
+        temp_dir.close().map_err(anyhow::Error::from)
+    }
+}